@@ -0,0 +1,90 @@
+use cite_me_bro::{
+    ops::bibligraphy::Bibliography,
+    parsing::citation::CitationSyntax,
+    styles::{ReferenceStyle, SortOrder},
+    Format,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// a bib file with `n` articles with keys `key0` up to `key{n-1}`
+fn bib_contents(n: usize) -> String {
+    (0..n)
+        .map(|i| {
+            format!(
+                "@article{{key{i},\n  author = {{Doe, Jane and Smith, John}},\n  title = {{Title number {i}}},\n  journal = {{Journal}},\n  volume = {},\n  pages = {{1--10}},\n  year = {},\n}}\n\n",
+                i % 50,
+                1950 + i % 70
+            )
+        })
+        .collect()
+}
+
+/// a text with `citations` citations spread over the keys of a bib file with `n` entries
+fn document(n: usize, citations: usize) -> String {
+    (0..citations)
+        .map(|i| format!("Some text \\cite{{key{}}} and more.\n", (i * 7919) % n))
+        .collect()
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_entry");
+    for n in [1_000, 10_000] {
+        let bib = Bibliography::from_bib_str(&bib_contents(n)).unwrap();
+        let keys: Vec<String> = (0..n).step_by(n / 100).map(|i| format!("key{i}")).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &keys, |b, keys| {
+            b.iter(|| keys.iter().filter_map(|k| bib.get_entry(k)).count())
+        });
+    }
+    group.finish();
+}
+
+fn expand(c: &mut Criterion) {
+    let mut group = c.benchmark_group("expand_citations_in_string");
+    group.sample_size(10);
+    for n in [1_000, 10_000] {
+        let bib = Bibliography::from_bib_str(&bib_contents(n)).unwrap();
+        let text = document(n, 1_000);
+        for markers in [false, true] {
+            let id = BenchmarkId::new(if markers { "markers" } else { "full" }, n);
+            group.bench_with_input(id, &text, |b, text| {
+                b.iter(|| {
+                    bib.expand_citations_in_string(
+                        text,
+                        ReferenceStyle::APA,
+                        Format::Plain,
+                        CitationSyntax::Latex,
+                        markers,
+                        SortOrder::Style,
+                        false,
+                    )
+                    .unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn format_filtered(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fmt_entries_filtered");
+    group.sample_size(10);
+    for n in [1_000, 10_000] {
+        let bib = Bibliography::from_bib_str(&bib_contents(n)).unwrap();
+        let keys: Vec<String> = (0..n).step_by(10).map(|i| format!("key{i}")).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &keys, |b, keys| {
+            b.iter(|| {
+                bib.fmt_entries_filtered(
+                    ReferenceStyle::IEEE,
+                    Format::Plain,
+                    keys.clone(),
+                    SortOrder::Style,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lookup, expand, format_filtered);
+criterion_main!(benches);
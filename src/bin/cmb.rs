@@ -1,7 +1,12 @@
 use anyhow::{anyhow, Result};
-use cite_me_bro::{ops::bibligraphy::Bibliography, styles::ReferenceStyle, Format};
-use clap::{crate_version, Parser};
-use std::path::PathBuf;
+use cite_me_bro::{
+    ops::{bibligraphy::Bibliography, duplicates::DuplicatePolicy},
+    parsing::citation::CitationSyntax,
+    styles::{ReferenceStyle, SortOrder},
+    Format,
+};
+use clap::{crate_version, Parser, Subcommand};
+use std::{fs, path::PathBuf};
 
 #[derive(Parser)]
 #[command(
@@ -22,21 +27,89 @@ struct Args {
     #[arg(short, long, value_enum, default_value_t = Format::Plain)]
     format: Format,
 
+    /// the order in which to print the references, or to number them in
+    #[arg(long, value_enum, default_value_t = SortOrder::Style)]
+    sort: SortOrder,
+
     /// the keys of the references to print. If none are provided all references will be printed
     keys: Vec<String>,
 
-    /// Instead of printing citations to stdout, replace instances of \cite{key}
-    /// in INPLACE_FILE with the corresponding reference
+    /// Instead of printing citations to stdout, replace citation commands like \cite{key},
+    /// \citet{key} or \parencite{key} in INPLACE_FILE with the corresponding reference or
+    /// in text citation
     #[arg(short, long, value_name = "INPLACE_FILE", conflicts_with = "keys")]
     inplace_file: Option<PathBuf>,
 
+    /// the syntax of the citations in INPLACE_FILE
+    #[arg(long, value_enum, default_value_t = CitationSyntax::Latex)]
+    syntax: CitationSyntax,
+
+    /// replace the citations in INPLACE_FILE with in text markers like [1] and put the
+    /// list of cited references at \printbibliography or the end of the file
+    #[arg(long)]
+    markers: bool,
+
     #[arg(long, default_value_t = false)]
     fail_fast: bool,
 
+    /// what to do with entries that have the same key as one in an earlier BIB_FILE
+    #[arg(long, value_enum, default_value_t = DuplicatePolicy::FirstWins)]
+    duplicates: DuplicatePolicy,
+
+    /// Skip malformed entries in the bib files with a warning instead of exiting
+    #[arg(long, default_value_t = false)]
+    lenient: bool,
+
     /// Do not pring warnings when citation keys are not found
     /// does nothing if no keys are provided
     #[arg(short, long, default_value_t = false)]
     quiet: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the clusters of entries that are probably the same work under different
+    /// keys, because they have the same DOI, ISBN or arXiv id or the same first author,
+    /// year and title
+    Dedup {
+        /// write the bib files to OUTPUT keeping only the first entry of each cluster
+        #[arg(short, long, value_name = "OUTPUT")]
+        output: Option<PathBuf>,
+    },
+}
+
+/// print the clusters of duplicates, optionally writing the deduplicated bib files
+fn dedup(
+    bibliography: &Bibliography,
+    bib_files: &[PathBuf],
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let clusters = bibliography.find_duplicates();
+    for cluster in &clusters {
+        let entries: Vec<String> = cluster
+            .iter()
+            .map(|key| match bibliography.source(key) {
+                Some(source) => format!("{} ({})", key, source),
+                None => key.clone(),
+            })
+            .collect();
+        println!("{}", entries.join(", "));
+    }
+    if let Some(output) = output {
+        let mut merged = String::new();
+        for file in bib_files {
+            let contents = fs::read_to_string(file)?;
+            if !merged.is_empty() {
+                merged.push('\n');
+            }
+            merged.push_str(&bibliography.without_duplicates(file, &contents, &clusters));
+        }
+        fs::write(output, merged)?;
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -46,24 +119,29 @@ fn main() -> Result<()> {
         .format_target(false)
         .format_timestamp(None)
         .init();
-    let mut bibliography = Bibliography::default();
-
-    for p in args.bib_files.clone() {
-        let tmp_bib = Bibliography::from_file(p)?;
-        bibliography.merge(tmp_bib);
-    }
+    // the files are loaded together so entries can cross reference entries in other files
+    let bibliography = if args.lenient {
+        Bibliography::from_files_lenient(args.bib_files.clone(), args.duplicates)?
+    } else {
+        Bibliography::from_files(args.bib_files.clone(), args.duplicates)?
+    };
 
-    if let Some(inplace_path) = args.inplace_file {
+    if let Some(Command::Dedup { output }) = args.command {
+        dedup(&bibliography, &args.bib_files, output)
+    } else if let Some(inplace_path) = args.inplace_file {
         bibliography.expand_file_citations_inplace(
             inplace_path,
             args.style,
             args.format,
+            args.syntax,
+            args.markers,
+            args.sort,
             args.fail_fast,
         )?;
         Ok(())
     } else if args.keys.is_empty() {
         bibliography
-            .fmt_entries(args.style, args.format)
+            .fmt_entries(args.style, args.format, args.sort)
             .into_iter()
             .for_each(|f| println!("{}", f));
         Ok(())
@@ -72,7 +150,7 @@ fn main() -> Result<()> {
             args.style,
             args.format,
             args.keys.clone(),
-            args.fail_fast,
+            args.sort,
         )?;
         if formatted.is_empty() && !args.quiet {
             Err(anyhow!(
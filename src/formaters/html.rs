@@ -15,15 +15,20 @@ impl Formatter for HtmlFormatter {
     }
 
     fn hyperlink(&self, input: &mut String) {
-        let dup = input.clone();
-        input.insert_str(0, "<a href=\"");
+        let url = input.clone();
+        input.clear();
+        input.push_str("<a href=\"");
+        html_escape::encode_double_quoted_attribute_to_string(&url, input);
         input.push_str("\">");
-        input.push_str(&dup);
+        html_escape::encode_text_to_string(&url, input);
         input.push_str("</a>");
     }
 
     fn verbatim(&self, input: &mut String) {
-        input.insert_str(0, "<pre>");
+        let text = input.clone();
+        input.clear();
+        input.push_str("<pre>");
+        html_escape::encode_text_to_string(text, input);
         input.push_str("</pre>");
     }
 
@@ -82,6 +87,13 @@ mod test {
         );
     }
     #[test]
+    fn markup_is_escaped() {
+        assert_eq!(
+            HtmlFormatter.markup("$x<y$ and <b>tags</b> in \\emph{a & b}"),
+            String::from("<i>x&lt;y</i> and &lt;b&gt;tags&lt;/b&gt; in <i>a &amp; b</i>")
+        );
+    }
+    #[test]
     fn verbatim() {
         let mut s = String::from("asdf  hey! this is _some_ weird__ input::!");
 
@@ -91,4 +103,22 @@ mod test {
             String::from("<pre>asdf  hey! this is _some_ weird__ input::!</pre>")
         );
     }
+
+    #[test]
+    fn verbatim_and_urls_are_escaped() {
+        assert_eq!(
+            HtmlFormatter.markup("\\texttt{a<b} \\url{https://a.b/?x=\"1\"&y=<2>}"),
+            String::from("<pre>a&lt;b</pre> <a href=\"https://a.b/?x=&quot;1&quot;&amp;y=&lt;2&gt;\">https://a.b/?x=\"1\"&amp;y=&lt;2&gt;</a>")
+        );
+    }
+
+    #[test]
+    fn markup() {
+        let s = HtmlFormatter
+            .markup("Growth of \\emph{E. coli} in \\textbf{bold} \\texttt{code} $x^2$ \\url{https://a.b} {NASA}");
+        assert_eq!(
+            s,
+            String::from("Growth of <i>E. coli</i> in <b>bold</b> <pre>code</pre> <i>x^2</i> <a href=\"https://a.b\">https://a.b</a> NASA")
+        );
+    }
 }
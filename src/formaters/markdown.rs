@@ -98,4 +98,16 @@ mod test {
             String::from("`asdf  hey! this is _some_ weird__ input::!`")
         );
     }
+
+    #[test]
+    fn markup() {
+        let s = MarkdownFormatter
+            .markup("Growth of \\emph{E. coli} in \\textbf{bold} \\texttt{code} $x^2$ \\url{https://a.b} {NASA}");
+        assert_eq!(
+            s,
+            String::from(
+                "Growth of *E. coli* in **bold** `code` *x^2* [https://a.b](https://a.b) NASA"
+            )
+        );
+    }
 }
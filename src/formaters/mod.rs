@@ -1,3 +1,5 @@
+use crate::parsing::markup::{parse_markup, Inline};
+
 pub mod html;
 pub mod markdown;
 pub mod plain;
@@ -10,4 +12,39 @@ pub trait Formatter {
     fn hyperlink(&self, input: &mut String);
     fn verbatim(&self, input: &mut String);
     fn escape(&self, input: &mut String);
+
+    /// render the inline formatting of a field using the methods above
+    fn render(&self, nodes: &[Inline]) -> String {
+        nodes
+            .iter()
+            .map(|node| {
+                let mut out = match node {
+                    Inline::Text(t) | Inline::Math(t) => {
+                        let mut text = t.clone();
+                        self.escape(&mut text);
+                        text
+                    }
+                    Inline::Verbatim(t) | Inline::Url(t) => t.clone(),
+                    Inline::Italic(children)
+                    | Inline::Bold(children)
+                    | Inline::Protected(children) => self.render(children),
+                };
+                match node {
+                    // the braces only matter for changing case, so they're left out
+                    Inline::Text(_) | Inline::Protected(_) => (),
+                    // math is mostly variables, which are set in italics
+                    Inline::Italic(_) | Inline::Math(_) => self.italics(&mut out),
+                    Inline::Bold(_) => self.bold(&mut out),
+                    Inline::Verbatim(_) => self.verbatim(&mut out),
+                    Inline::Url(_) => self.hyperlink(&mut out),
+                };
+                out
+            })
+            .collect()
+    }
+
+    /// render a field value that may contain LaTeX formatting commands like `\emph{}`
+    fn markup(&self, input: &str) -> String {
+        self.render(&parse_markup(input))
+    }
 }
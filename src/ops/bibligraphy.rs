@@ -1,165 +1,686 @@
 use anyhow::Result;
 use log::warn;
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     fs::{self, read_to_string, File},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use nom::{combinator::all_consuming, multi::many1};
+use crate::{
+    ops::{
+        crossref::{is_xdata, resolve_inheritance, DanglingReference},
+        duplicates::{find_duplicates, merge_fields, DuplicateKey, DuplicatePolicy, EntrySource},
+    },
+    parsing::error::{line_number, offset_in, BibParseError},
+    styles::{Cited, ReferenceStyle, SortOrder},
+    Format,
+};
 
-use crate::{styles::ReferenceStyle, Format};
+use crate::parsing::{
+    citation::{all_bibliography_placeholders, Citation, CitationMode, CitationSyntax},
+    entry::{
+        entry_text, expand_field_value, parse_bib, parse_bib_lenient, predefined_macros, BibEntry,
+        BibItem, InvalidField,
+    },
+    latex::decode_latex,
+};
 
-use crate::parsing::entry::{all_citations, entry, BibEntry, EntrySubComponents};
+/// The number of cited entries that have to cross reference an entry before it is
+/// included in the bibliography itself, like bibtex's `min-crossrefs`
+pub const MIN_CROSSREFS: usize = 2;
 
 #[derive(Default)]
 pub struct Bibliography {
+    /// the entries in the order they were defined, with the fields they inherit from
+    /// their `crossref` and `xdata` parents
     entries: Vec<BibEntry>,
+    /// the entries with only the fields they were written with, which inheritance is
+    /// resolved from whenever entries are added or replaced
+    own: Vec<BibEntry>,
+    /// where each of the entries was defined
+    sources: Vec<EntrySource>,
+    /// the position of each key in `entries`, `own` and `sources`
+    index: HashMap<String, usize>,
+    preambles: Vec<String>,
+    diagnostics: Vec<BibParseError>,
+    dangling: Vec<DanglingReference>,
+    duplicates: Vec<DuplicateKey>,
 }
 
 impl Bibliography {
-    pub fn get_entry(&self, key: String) -> Option<BibEntry> {
-        self.entries.iter().find(|&e| e.key == key).cloned()
+    /// errors for the entries that were skipped while parsing leniently.
+    /// always empty when parsing strictly
+    pub fn diagnostics(&self) -> &[BibParseError] {
+        &self.diagnostics
     }
 
-    pub fn fmt_entries(self, style: ReferenceStyle, format: Format) -> Vec<String> {
-        self.entries
+    /// `crossref` and `xdata` fields that refer to entries that don't exist
+    pub fn dangling_references(&self) -> &[DanglingReference] {
+        &self.dangling
+    }
+
+    /// entries that had the same key as one loaded before them, see [`Bibliography::merge`]
+    pub fn duplicates(&self) -> &[DuplicateKey] {
+        &self.duplicates
+    }
+
+    /// where the entry with the given key was defined
+    pub fn source(&self, key: &str) -> Option<&EntrySource> {
+        self.index.get(key).map(|i| &self.sources[*i])
+    }
+
+    /// Entries that are probably the same work under different keys, see
+    /// [`find_duplicates`]. The first key of each cluster is the one to keep: the entry
+    /// with the most fields, or the first of those.
+    pub fn find_duplicates(&self) -> Vec<Vec<String>> {
+        find_duplicates(&self.entries)
             .into_iter()
-            .map(|b| style.fmt_reference(b, format))
+            .map(|mut cluster| {
+                let canonical = cluster
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(pos, i)| (self.entries[**i].fields.len(), usize::MAX - pos))
+                    .map_or(0, |(pos, _)| pos);
+                let first = cluster.remove(canonical);
+                cluster.insert(0, first);
+                cluster
+                    .into_iter()
+                    .map(|i| self.entries[i].key.clone())
+                    .collect()
+            })
             .collect()
     }
-    pub fn has_key(&self, key: &String) -> bool {
-        self.entries.iter().any(|e| &e.key == key)
+
+    /// The contents of one of the bib files this bibliography was loaded from, without
+    /// the entries that aren't the first of their cluster of duplicates, and without the
+    /// definitions of a repeated key that weren't kept by the [`DuplicatePolicy`]
+    pub fn without_duplicates(
+        &self,
+        file: &Path,
+        contents: &str,
+        clusters: &[Vec<String>],
+    ) -> String {
+        let mut removed: Vec<&EntrySource> = clusters
+            .iter()
+            .flat_map(|c| c.iter().skip(1))
+            .filter_map(|key| self.source(key))
+            .collect();
+        for duplicate in &self.duplicates {
+            let kept = self.source(&duplicate.key);
+            for source in [&duplicate.first, &duplicate.second] {
+                if Some(source) != kept && !removed.contains(&source) {
+                    removed.push(source);
+                }
+            }
+        }
+        // entries are removed from the end, so the positions of the others stay the same
+        let mut ranges: Vec<(usize, usize)> = removed
+            .into_iter()
+            .filter(|s| s.file.as_deref() == Some(file))
+            .filter_map(|s| position_in(contents, s))
+            .collect();
+        ranges.sort();
+        ranges.dedup();
+        let mut out = contents.to_string();
+        for (start, len) in ranges.into_iter().rev() {
+            let rest = &out[start + len..];
+            let end = out.len() - rest.trim_start().len();
+            out.replace_range(start..end, "");
+        }
+        out
+    }
+
+    /// the (macro expanded) contents of any `@preamble` blocks, in the order they appeared
+    pub fn preambles(&self) -> &[String] {
+        &self.preambles
+    }
+
+    pub fn get_entry(&self, key: &str) -> Option<&BibEntry> {
+        self.index.get(key).map(|i| &self.entries[*i])
+    }
+
+    /// the entries in the order they were defined
+    pub fn entries(&self) -> &[BibEntry] {
+        &self.entries
     }
+
+    /// add an entry, or replace the one with the same key. Inheritance has to be resolved
+    /// again afterwards
+    fn insert(&mut self, entry: BibEntry, source: EntrySource) {
+        match self.index.get(&entry.key) {
+            Some(i) => {
+                self.own[*i] = entry;
+                self.sources[*i] = source;
+            }
+            None => {
+                self.index.insert(entry.key.clone(), self.own.len());
+                self.own.push(entry);
+                self.sources.push(source);
+            }
+        }
+    }
+
+    pub fn fmt_entries(
+        &self,
+        style: ReferenceStyle,
+        format: Format,
+        order: SortOrder,
+    ) -> Vec<String> {
+        let mut entries: Vec<Cited> = self
+            .entries
+            .iter()
+            .filter(|e| !is_xdata(e))
+            .enumerate()
+            .map(|(i, e)| Cited::new(e, i + 1))
+            .collect();
+        style.sort(&mut entries, order);
+        style.disambiguate(&mut entries);
+        style.fmt_references(&entries, format)
+    }
+    pub fn has_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// The entries that are cross referenced by at least `min_crossrefs` of the entries
+    /// with the given keys, but aren't one of them, in the order they are in the bibliography
+    pub fn crossref_parents(&self, keys: &[String], min_crossrefs: usize) -> Vec<String> {
+        let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for entry in keys.iter().filter_map(|k| self.get_entry(k)) {
+            if let Some(parent) = entry.fields.get("crossref") {
+                *counts.entry(parent.trim()).or_default() += 1;
+            }
+        }
+        self.entries
+            .iter()
+            .filter(|e| !keys.contains(e.key.as_str()))
+            .filter(|e| {
+                counts
+                    .get(e.key.as_str())
+                    .is_some_and(|c| *c >= min_crossrefs)
+            })
+            .map(|e| e.key.clone())
+            .collect()
+    }
+    /// Format the entries with the given keys. The keys that aren't in the bibliography
+    /// are returned as well, so the caller can decide what to do about them.
     pub fn fmt_entries_filtered(
-        self,
+        &self,
         style: ReferenceStyle,
         format: Format,
         keys: Vec<String>,
-        fail_fast: bool,
+        order: SortOrder,
     ) -> Result<(Vec<String>, Vec<String>)> {
-        let (known_keys, unknown_keys): (Vec<String>, Vec<String>) =
+        let (mut known_keys, unknown_keys): (Vec<String>, Vec<String>) =
             keys.into_iter().partition(|e| self.has_key(e));
-        let formatted: Vec<String> = known_keys
-            .into_iter()
-            .map(|b| {
-                let entry = self.get_entry(b.clone());
-                match entry {
-                    Some(e) => Ok(style.fmt_reference(e, format)),
-                    None => {
-                        if fail_fast {
-                            Err(anyhow::Error::msg(format!("key {} not found", b)))
-                        } else {
-                            warn!("key {} not found", b.clone());
-                            Ok(b)
-                        }
-                    }
-                }
-            })
-            .collect::<Result<Vec<String>>>()?;
-        Ok((formatted, unknown_keys))
+        // like bibtex, entries that are cross referenced often enough are included as well
+        known_keys.extend(self.crossref_parents(&known_keys, MIN_CROSSREFS));
+        let mut entries = Vec::new();
+        for key in known_keys {
+            if let Some(e) = self.get_entry(&key) {
+                entries.push(Cited::new(e, entries.len() + 1));
+            }
+        }
+        style.sort(&mut entries, order);
+        style.disambiguate(&mut entries);
+        Ok((style.fmt_references(&entries, format), unknown_keys))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn expand_file_citations_inplace(
         &self,
         path: PathBuf,
         style: ReferenceStyle,
         format: Format,
+        syntax: CitationSyntax,
+        markers: bool,
+        order: SortOrder,
         fail_fast: bool,
     ) -> Result<()> {
         let mut contents = read_to_string(&path)?;
-        contents = self.expand_citations_in_string(&contents, style, format, fail_fast)?;
+        contents = self.expand_citations_in_string(
+            &contents, style, format, syntax, markers, order, fail_fast,
+        )?;
         let mut file = File::create(&path)?;
         file.write_all(contents.as_bytes()).unwrap();
         Ok(())
     }
 
+    /// Expand the citations in a text. With `markers` citations become in text markers
+    /// like "[1]" and the reference list of the cited entries is put in place of
+    /// `\printbibliography` or `\bibliography{...}`, or at the end if there is neither.
+    /// Otherwise `\cite` is replaced by the full reference. Numbered styles number the
+    /// entries in the `order` of the reference list.
+    #[allow(clippy::too_many_arguments)]
     pub fn expand_citations_in_string(
         &self,
         contents: &str,
         style: ReferenceStyle,
         format: Format,
+        syntax: CitationSyntax,
+        markers: bool,
+        order: SortOrder,
         fail_fast: bool,
     ) -> Result<String> {
-        let (tail, segments) = all_citations(contents).unwrap();
-        let mut acc = segments.into_iter().try_fold(
-            String::new(),
-            |mut acc, (unmodified, citation_key)| {
-                acc.push_str(unmodified);
-
-                match self
-                    .get_entry(citation_key.to_string())
-                    .map(|entry| style.fmt_reference(entry, format))
-                {
-                    Some(formatted) => {
-                        acc.push_str(&formatted);
-                        Ok(acc)
-                    }
-                    None => {
-                        if fail_fast {
-                            Err(anyhow::Error::msg(format!(
-                                "key {} was not present in any of the bib files",
-                                &citation_key
-                            )))
-                        } else {
-                            warn!("Key {} in text was not found, skipping...", &citation_key);
-                            acc.push_str("\\cite{");
-                            acc.push_str(citation_key);
-                            acc.push('}');
-                            Ok(acc)
-                        }
-                    }
+        let (tail, mut segments) = syntax.all_citations(contents).unwrap();
+        if markers {
+            for (_, citation) in segments.iter_mut() {
+                if citation.mode == CitationMode::Full {
+                    citation.mode = CitationMode::Parenthetical;
                 }
-            },
-        )?;
+            }
+        }
+        let cited = self.cited_entries(&segments, style, order, markers);
+        let cited_by_key: HashMap<&str, &Cited> =
+            cited.iter().map(|c| (c.entry.key.as_str(), c)).collect();
+        let mut acc = String::new();
+        for (unmodified, citation) in segments {
+            acc.push_str(unmodified);
+            acc.push_str(&self.expand_citation(
+                &citation,
+                &cited_by_key,
+                style,
+                format,
+                fail_fast,
+            )?);
+        }
 
         acc.push_str(tail);
 
-        Ok(acc)
+        if !markers {
+            return Ok(acc);
+        }
+        let references = style.fmt_bibliography(cited, format).join("\n\n");
+        let (tail, placeholders) = all_bibliography_placeholders(&acc).unwrap();
+        if placeholders.is_empty() {
+            if !references.is_empty() {
+                if !acc.is_empty() && !acc.ends_with('\n') {
+                    acc.push('\n');
+                }
+                acc.push('\n');
+                acc.push_str(&references);
+                acc.push('\n');
+            }
+            return Ok(acc);
+        }
+        let mut out = String::new();
+        for (unmodified, _) in placeholders {
+            out.push_str(unmodified);
+            out.push_str(&references);
+        }
+        out.push_str(tail);
+        Ok(out)
+    }
+
+    /// The entries cited in the text in the order of the reference list, numbered by that
+    /// order and disambiguated as the style requires. Citations with keys that can't be
+    /// found are left out, as they are left as written. With a reference list the entries
+    /// they cross reference often enough are included, like [`Bibliography::fmt_entries_filtered`]
+    fn cited_entries(
+        &self,
+        citations: &[(&str, Citation)],
+        style: ReferenceStyle,
+        order: SortOrder,
+        with_crossrefs: bool,
+    ) -> Vec<Cited<'_>> {
+        let mut keys: Vec<String> = Vec::new();
+        for (_, citation) in citations {
+            if !citation.keys.iter().all(|k| *k == "*" || self.has_key(k)) {
+                continue;
+            }
+            for key in &citation.keys {
+                // `\nocite{*}` adds every entry to the bibliography
+                let added: Vec<String> = match (citation.mode, *key) {
+                    (CitationMode::NoCite, "*") => self
+                        .entries
+                        .iter()
+                        .filter(|e| !is_xdata(e))
+                        .map(|e| e.key.clone())
+                        .collect(),
+                    _ => vec![key.to_string()],
+                };
+                for key in added {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+        if with_crossrefs {
+            keys.extend(self.crossref_parents(&keys, MIN_CROSSREFS));
+        }
+        let mut cited: Vec<Cited> = keys
+            .into_iter()
+            .filter_map(|key| self.get_entry(&key))
+            .enumerate()
+            .map(|(i, entry)| Cited::new(entry, i + 1))
+            .collect();
+        style.sort(&mut cited, order);
+        for (i, c) in cited.iter_mut().enumerate() {
+            c.number = i + 1;
+        }
+        style.disambiguate(&mut cited);
+        cited
     }
 
+    /// The text a citation is replaced with, which depends on the citation command. If any
+    /// of the keys can't be found the citation is left as it was written.
+    fn expand_citation(
+        &self,
+        citation: &Citation,
+        cited_entries: &HashMap<&str, &Cited>,
+        style: ReferenceStyle,
+        format: Format,
+        fail_fast: bool,
+    ) -> Result<String> {
+        let mut missing = false;
+        for key in &citation.keys {
+            if citation.mode == CitationMode::NoCite && *key == "*" {
+                continue;
+            }
+            if self.has_key(key) {
+                continue;
+            }
+            if fail_fast {
+                return Err(anyhow::Error::msg(format!(
+                    "key {} was not present in any of the bib files",
+                    key
+                )));
+            }
+            warn!("Key {} in text was not found, skipping...", key);
+            missing = true;
+        }
+        if missing {
+            return Ok(citation.raw.to_string());
+        }
+        let cited: Vec<Cited> = citation
+            .keys
+            .iter()
+            .filter_map(|key| cited_entries.get(key))
+            .map(|c| (*c).clone())
+            .collect();
+
+        let prenote = citation.prenote.map(decode_latex);
+        let postnote = citation.postnote.map(decode_latex);
+        match citation.mode {
+            CitationMode::Full => (),
+            CitationMode::NoCite => return Ok(String::new()),
+            mode => {
+                return Ok(style.fmt_citation(
+                    cited,
+                    mode,
+                    prenote.as_deref(),
+                    postnote.as_deref(),
+                    format,
+                ))
+            }
+        }
+
+        let references = style.fmt_references(&cited, format);
+        let mut out = String::new();
+        if let Some(prenote) = prenote {
+            out.push_str(&prenote);
+            out.push(' ');
+        }
+        out.push_str(&references.join("; "));
+        if let Some(postnote) = postnote {
+            // the locator goes before the final period of the reference
+            if out.ends_with('.') {
+                out.pop();
+            }
+            out.push_str(", ");
+            out.push_str(&postnote);
+            if !out.ends_with('.') {
+                out.push('.');
+            }
+        }
+        Ok(out)
+    }
+
+    /// Load a bib file. Of entries with the same key the first is kept, see
+    /// [`Bibliography::from_files`] to choose another [`DuplicatePolicy`]
     pub fn from_file(path: PathBuf) -> Result<Self> {
+        Self::from_files(vec![path], DuplicatePolicy::default())
+    }
+
+    /// Like [`Bibliography::from_file`] but malformed entries are skipped instead of
+    /// failing the whole file. A warning is logged for every skipped entry and
+    /// they can be inspected with [`Bibliography::diagnostics`]
+    pub fn from_file_lenient(path: PathBuf) -> Result<Self> {
+        Self::from_files_lenient(vec![path], DuplicatePolicy::default())
+    }
+
+    fn load_file(path: PathBuf, lenient: bool) -> Result<Self> {
         if !&path.exists() {
             return Err(anyhow::Error::msg(format!(
                 "File {} does not exist",
                 path.display()
             )));
         }
-        let contents = fs::read_to_string(path)?;
+        let contents = fs::read_to_string(&path)?;
+        let mut bib =
+            Self::parse_bib_str(&contents, lenient).map_err(|e| e.with_file(path.clone()))?;
+        for source in bib.sources.iter_mut() {
+            source.file = Some(path.clone());
+        }
+        for diagnostic in bib.diagnostics.iter_mut() {
+            diagnostic.file = Some(path.clone());
+            warn!("skipping malformed entry: {}", diagnostic);
+        }
+        Ok(bib)
+    }
+
+    /// parse a bibliography from the contents of a bib file. Of entries with the same key
+    /// the first is kept
+    pub fn from_bib_str(contents: &str) -> Result<Self> {
+        let mut out = Self::default();
+        out.add(
+            Self::parse_bib_str(contents, false)?,
+            DuplicatePolicy::default(),
+        )?;
+        Ok(out.resolved())
+    }
+
+    /// parse a bibliography from the contents of a bib file, skipping malformed entries
+    pub fn from_bib_str_lenient(contents: &str) -> Self {
+        let mut out = Self::default();
+        // in lenient mode all errors end up in the diagnostics, and the first of entries
+        // with the same key is kept without failing
+        let _ = out.add(
+            Self::parse_bib_str(contents, true).unwrap_or_default(),
+            DuplicatePolicy::default(),
+        );
+        out.resolved()
+    }
+
+    /// resolve `crossref` and `xdata` inheritance, warning about any dangling references
+    fn resolved(mut self) -> Self {
+        self.resolve_inheritance();
+        for dangling in &self.dangling {
+            warn!("{}", dangling);
+        }
+        self
+    }
+
+    /// inherit from the entries as they were written, so a parent that was replaced
+    /// doesn't leave its fields behind in its children
+    fn resolve_inheritance(&mut self) {
+        self.entries = self.own.clone();
+        self.dangling = resolve_inheritance(&mut self.entries, &self.index);
+    }
+
+    /// The entries of a bib file as they were written, including any with the same key.
+    /// They are only indexed once they are added to a bibliography, see [`Bibliography::add`]
+    fn parse_bib_str(contents: &str, lenient: bool) -> Result<Self, BibParseError> {
+        let (items, mut diagnostics) = if lenient {
+            parse_bib_lenient(contents)
+        } else {
+            (parse_bib(contents)?, Vec::new())
+        };
+
+        // macros have to be defined before they are used, so we go through
+        // the items in order
+        let mut macros = predefined_macros();
+        let mut entry_vec: Vec<BibEntry> = Vec::new();
+        let mut sources = Vec::new();
+        let mut preambles = Vec::new();
+        // the entries come in order, so the lines are counted from the previous one
+        let (mut counted, mut line) = (contents, 1);
+        for item in items {
+            match item {
+                BibItem::StringDefinition(name, value) => {
+                    let expanded = expand_field_value(&value, &macros);
+                    macros.insert(name.to_lowercase(), expanded);
+                }
+                BibItem::Entry(components) => {
+                    let key = components.1;
+                    let field_names: Vec<&str> = components.2.iter().map(|(k, _)| *k).collect();
+                    match BibEntry::from_components(components, &macros) {
+                        Ok(entry) => {
+                            entry_vec.push(entry);
+                            line += line_number(counted, key) - 1;
+                            counted = &counted[offset_in(counted, key)..];
+                            sources.push(EntrySource {
+                                file: None,
+                                line,
+                                text: entry_text(contents, key).to_string(),
+                            });
+                        }
+                        Err(e) => {
+                            // errors in a field point at its name, others at the key
+                            let at = e
+                                .downcast_ref::<InvalidField>()
+                                .and_then(|invalid| {
+                                    field_names
+                                        .iter()
+                                        .find(|k| k.eq_ignore_ascii_case(&invalid.field))
+                                })
+                                .copied()
+                                .unwrap_or(key);
+                            let err = BibParseError::new(contents, at, e.to_string()).with_key(key);
+                            if lenient {
+                                diagnostics.push(err);
+                            } else {
+                                return Err(err);
+                            }
+                        }
+                    }
+                }
+                BibItem::Preamble(value) => preambles.push(expand_field_value(&value, &macros)),
+                BibItem::Comment(_) => (),
+            }
+        }
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        Ok(Self {
+            index: HashMap::new(),
+            // filled in when inheritance is resolved
+            entries: Vec::new(),
+            own: entry_vec,
+            sources,
+            preambles,
+            diagnostics,
+            dangling: Vec::new(),
+            duplicates: Vec::new(),
+        })
+    }
 
-        let (_tail, entries): (&str, Vec<EntrySubComponents>) =
-            all_consuming(many1(entry))(&contents).unwrap();
-        let entry_vec: Vec<BibEntry> = entries.into_iter().map(|t| t.into()).collect();
-        Ok(entry_vec.into())
+    /// Load and merge several bib files, handling entries with the same key according to
+    /// `policy`, see [`Bibliography::merge`]
+    pub fn from_files(path: Vec<PathBuf>, policy: DuplicatePolicy) -> Result<Self> {
+        Self::load_files(path, false, policy)
     }
 
-    pub fn from_files(path: Vec<PathBuf>) -> Result<Self> {
+    /// Like [`Bibliography::from_files`] but skips malformed entries, see [`Bibliography::from_file_lenient`]
+    pub fn from_files_lenient(path: Vec<PathBuf>, policy: DuplicatePolicy) -> Result<Self> {
+        Self::load_files(path, true, policy)
+    }
+
+    fn load_files(path: Vec<PathBuf>, lenient: bool, policy: DuplicatePolicy) -> Result<Self> {
         let mut out = Self::default();
         let results = path
             .into_iter()
             .map(|p| {
-                let new_bib = Bibliography::from_file(p)?;
-                out.merge(new_bib);
+                let new_bib = Bibliography::load_file(p, lenient)?;
+                out.add(new_bib, policy)?;
                 Ok(())
             })
             .collect::<Result<Vec<()>, anyhow::Error>>();
         match results {
-            Ok(_) => Ok(out),
+            Ok(_) => Ok(out.resolved()),
             Err(e) => Err(e),
         }
     }
 
-    /// merge the two bibliographies by consuming the other.
-    /// currently citation conflicts are not handles yet.
-    pub fn merge(&mut self, other: Bibliography) -> &mut Self {
-        self.entries.extend(other.entries);
-        self
+    /// merge the two bibliographies by consuming the other. Entries with a key that is
+    /// already in the bibliography, including ones repeated within the other, are handled
+    /// according to `policy`. Cross references between the two are resolved afterwards.
+    pub fn merge(&mut self, other: Bibliography, policy: DuplicatePolicy) -> Result<&mut Self> {
+        self.add(other, policy)?;
+        self.resolve_inheritance();
+        Ok(self)
     }
+
+    /// [`Bibliography::merge`] without resolving inheritance, so that can be done once
+    /// all bibliographies are added
+    fn add(&mut self, other: Bibliography, policy: DuplicatePolicy) -> Result<()> {
+        for (entry, source) in other.own.into_iter().zip(other.sources) {
+            let i = match self.index.get(&entry.key) {
+                Some(i) => *i,
+                None => {
+                    self.insert(entry, source);
+                    continue;
+                }
+            };
+            let duplicate = DuplicateKey {
+                key: entry.key.clone(),
+                first: self.sources[i].clone(),
+                second: source.clone(),
+            };
+            match policy {
+                DuplicatePolicy::Error => return Err(anyhow::Error::msg(duplicate.to_string())),
+                DuplicatePolicy::FirstWins => warn!("{}, keeping the first", duplicate),
+                DuplicatePolicy::LastWins => {
+                    warn!("{}, keeping the last", duplicate);
+                    self.insert(entry, source);
+                }
+                DuplicatePolicy::Merge => {
+                    warn!("{}, merging their fields", duplicate);
+                    merge_fields(&mut self.own[i], entry);
+                }
+            }
+            self.duplicates.push(duplicate);
+        }
+        self.preambles.extend(other.preambles);
+        self.diagnostics.extend(other.diagnostics);
+        Ok(())
+    }
+}
+
+/// The start and length of the text of an entry in the contents of its file. The text of
+/// an entry that is written more than once is told apart by the line its key is on.
+fn position_in(contents: &str, source: &EntrySource) -> Option<(usize, usize)> {
+    contents
+        .match_indices(&source.text)
+        .map(|(start, text)| (start, text.len()))
+        .find(|(start, len)| {
+            let first = contents[..*start].matches('\n').count() + 1;
+            let last = first + contents[*start..start + len].matches('\n').count();
+            (first..=last).contains(&source.line)
+        })
 }
 
+/// Entries with the same key are handled like in [`Bibliography::from_bib_str`]
 impl From<Vec<BibEntry>> for Bibliography {
     fn from(value: Vec<BibEntry>) -> Self {
-        Self { entries: value }
+        let mut out = Self::default();
+        for entry in value {
+            if !out.has_key(&entry.key) {
+                out.insert(entry, EntrySource::default());
+            }
+        }
+        out.entries = out.own.clone();
+        out
     }
 }
 
@@ -173,7 +694,10 @@ mod test {
     use anyhow::Result;
     // lint allows are just while developing, will be removed soon
 
-    use crate::parsing::{entry::EntryType, names::OwnedFullName};
+    use crate::parsing::{
+        entry::EntryType,
+        names::{NameKind, OwnedFullName},
+    };
 
     #[test]
     fn test_bib_file_parse() -> Result<()> {
@@ -200,8 +724,12 @@ mod test {
                     first: vec!["Leo".to_string()],
                     last: vec!["Breiman".to_string()],
                     von: Vec::new(),
+                    jr: Vec::new(),
+                    kind: NameKind::Person,
+                    langid: None,
                     title: Vec::new()
                 }],
+                names: BTreeMap::new(),
                 fields: dict,
             }
         );
@@ -234,36 +762,54 @@ mod test {
                         first: vec!["Jingqiu".to_string()],
                         last: vec!["Liao".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
                         title: Vec::new()
                     },
                     OwnedFullName {
                         first: vec!["Xiaofeng".to_string()],
                         last: vec!["Cao".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
                         title: Vec::new()
                     },
                     OwnedFullName {
                         first: vec!["Lei".to_string()],
                         last: vec!["Zhao".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
                         title: Vec::new()
                     },
                     OwnedFullName {
                         first: vec!["Jie".to_string()],
                         last: vec!["Wang".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
                         title: Vec::new()
                     },
                     OwnedFullName {
                         first: vec!["Zhe".to_string()],
                         last: vec!["Gao".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
                         title: Vec::new()
                     },
                     OwnedFullName {
                         first: vec!["Michael".to_string(), "Cai".to_string()],
                         last: vec!["Wang".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
 
                         title: Vec::new()
                     },
@@ -271,12 +817,766 @@ mod test {
                         first: vec!["Yi".to_string()],
                         last: vec!["Huang".to_string()],
                         von: Vec::new(),
+                        jr: Vec::new(),
+                        kind: NameKind::Person,
+                        langid: None,
                         title: Vec::new()
                     },
                 ],
+                names: BTreeMap::new(),
                 fields: dict,
             }
         );
         Ok(())
     }
+
+    #[test]
+    fn string_macros_are_expanded() -> Result<()> {
+        let contents = "@string{jmlr = \"Journal of Machine Learning Research\"}
+        @STRING{ special = { (Special Issue)} }
+        @string{jmlrsi = jmlr # special}
+        @article{foo,
+            title = \"Foo\",
+            journal = jmlr # \" (Special Issue)\",
+            series = JMLRSI,
+            note = undefined # {, really},
+            year = 2020,
+        }";
+        let bib = Bibliography::from_bib_str(contents)?;
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            entry.fields.get("journal").unwrap(),
+            "Journal of Machine Learning Research (Special Issue)"
+        );
+        assert_eq!(
+            entry.fields.get("series").unwrap(),
+            "Journal of Machine Learning Research (Special Issue)"
+        );
+        assert_eq!(entry.fields.get("note").unwrap(), "undefined, really");
+        assert_eq!(entry.fields.get("year").unwrap(), "2020");
+        Ok(())
+    }
+
+    #[test]
+    fn latex_is_decoded() -> Result<()> {
+        let contents = r#"@article{foo,
+            author = {Schr{\"o}dinger, Erwin and Fran\c{c}ois Vi\`{e}te},
+            title = {Die gegenw{\"a}rtige Situation in der {Quantenmechanik}},
+            journal = {Naturwissenschaften},
+            pages = {807--812},
+            url = {https://example.com/~erwin/a--b},
+        }"#;
+        let bib = Bibliography::from_bib_str(contents)?;
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(entry.authors[0].last, vec!["Schrödinger"]);
+        assert_eq!(entry.authors[1].first, vec!["François"]);
+        assert_eq!(
+            entry.fields.get("title").unwrap(),
+            "Die gegenwärtige Situation in der {Quantenmechanik}"
+        );
+        assert_eq!(entry.fields.get("pages").unwrap(), "807–812");
+        assert_eq!(
+            entry.fields.get("url").unwrap(),
+            "https://example.com/~erwin/a--b"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_error_has_location() -> Result<()> {
+        let contents = "@misc{foo,
+            title = {Foo},
+        }
+        @misc{bar,
+            title = {Bar},
+            author = {and},
+        }";
+        let err = Bibliography::from_bib_str(contents)
+            .err()
+            .and_then(|e| e.downcast::<BibParseError>().ok())
+            .unwrap();
+        assert_eq!(err.key, Some("bar".to_string()));
+        // the error points at the name list that couldn't be parsed
+        assert_eq!((err.line, err.column), (6, 13));
+        Ok(())
+    }
+
+    #[test]
+    fn unparsed_rest_of_names_is_an_error() -> Result<()> {
+        let contents = "@misc{foo,
+  title = {Foo},
+  Editor = {Jane Doe and John Smith and},
+}";
+        let err = Bibliography::from_bib_str(contents)
+            .err()
+            .and_then(|e| e.downcast::<BibParseError>().ok())
+            .unwrap();
+        assert_eq!(err.key, Some("foo".to_string()));
+        assert_eq!((err.line, err.column), (3, 3));
+        assert_eq!(
+            err.expected,
+            "a list of names seperated by `and` in field `editor`, found `and`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn names_can_wrap_across_lines() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo,
+  title = {Foo},
+  author = {Doe, Jane and
+    Smith, John and Erwin
+    Schr{\\\"o}dinger},
+}",
+        )?;
+        let authors = &bib.get_entry("foo").unwrap().authors;
+        let last: Vec<&str> = authors.iter().map(|a| a.last[0].as_str()).collect();
+        assert_eq!(last, vec!["Doe", "Smith", "Schrödinger"]);
+        assert_eq!(authors[2].first, vec!["Erwin"]);
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_parse_skips_malformed_entries() -> Result<()> {
+        let contents = "@misc{foo,
+            title = {Foo},
+        }
+        @misc{bar,
+            title = {Bar},
+            author = {and},
+        }
+        @misc{baz,
+            title {Baz},
+        }
+        @misc{qux,
+            title = {Qux},
+        }";
+        assert!(Bibliography::from_bib_str(contents).is_err());
+        let bib = Bibliography::from_bib_str_lenient(contents);
+        assert_eq!(
+            bib.entries
+                .iter()
+                .map(|e| e.key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["foo", "qux"]
+        );
+        assert_eq!(
+            bib.diagnostics()
+                .iter()
+                .map(|d| (d.key.as_deref(), d.line))
+                .collect::<Vec<_>>(),
+            vec![(Some("bar"), 6), (Some("baz"), 9)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn comments_and_preambles() -> Result<()> {
+        let contents = "% exported by JabRef
+        @preamble{\"\\providecommand{\\noopsort}[1]{}\"}
+        @misc{foo,
+            title = {Foo},
+        }
+        The entry above is just a test.
+        @Comment{jabref-meta: databaseType:bibtex;}
+        ";
+        let bib = Bibliography::from_bib_str(contents)?;
+        assert!(bib.has_key("foo"));
+        assert_eq!(bib.entries.len(), 1);
+        assert_eq!(
+            bib.preambles(),
+            &["\\providecommand{\\noopsort}[1]{}".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn month_macros_are_predefined() -> Result<()> {
+        let contents = r#"@string{dec = "the holidays"}
+        @misc{foo, title = {Foo}, year = 2020, month = jan}
+        @misc{bar, title = {Bar}, year = 2020, month = dec}"#;
+        let bib = Bibliography::from_bib_str(contents)?;
+        let month = |key: &str| bib.get_entry(key).unwrap().fields["month"].clone();
+        assert_eq!(month("foo"), "January");
+        // they can still be redefined
+        assert_eq!(month("bar"), "the holidays");
+        Ok(())
+    }
+
+    const CROSSREFS: &str = r"@inproceedings{first,
+        author = {Jane Doe},
+        title = {First paper},
+        pages = {1--10},
+        crossref = {conf},
+    }
+    @inproceedings{second,
+        author = {John Smith},
+        title = {Second paper},
+        pages = {11--20},
+        crossref = {conf},
+    }
+    @proceedings{conf,
+        editor = {Bob Johnson},
+        title = {Proceedings of the Conference},
+        publisher = {Springer},
+        address = {Berlin},
+        series = {LNCS},
+        year = 2020,
+    }";
+
+    #[test]
+    fn crossrefs_are_resolved() -> Result<()> {
+        let bib = Bibliography::from_bib_str(CROSSREFS)?;
+        let (formatted, _) = bib.fmt_entries_filtered(
+            ReferenceStyle::IEEE,
+            Format::Plain,
+            vec!["first".to_string()],
+            SortOrder::Style,
+        )?;
+        assert_eq!(
+            formatted,
+            vec!["J. Doe, \"First Paper,\" in Proceedings of the Conference, ser. LNCS, Berlin: Springer, 2020, pp. 1–10."]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn often_cross_referenced_entries_are_included() -> Result<()> {
+        let bib = Bibliography::from_bib_str(CROSSREFS)?;
+        let keys = vec!["first".to_string(), "second".to_string()];
+        assert_eq!(
+            bib.crossref_parents(&keys[..1], MIN_CROSSREFS),
+            Vec::<String>::new()
+        );
+        assert_eq!(bib.crossref_parents(&keys, MIN_CROSSREFS), vec!["conf"]);
+        let (formatted, _) =
+            bib.fmt_entries_filtered(ReferenceStyle::APA, Format::Plain, keys, SortOrder::None)?;
+        assert_eq!(formatted.len(), 3);
+        assert_eq!(
+            formatted[2],
+            "Johnson, B. (Ed.). (2020). Proceedings of the conference. Springer."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn crossrefs_across_files() -> Result<()> {
+        let (children, parent) = CROSSREFS.split_at(CROSSREFS.find("@proceedings").unwrap());
+        let mut bib = Bibliography::from_bib_str(children)?;
+        assert_eq!(bib.dangling_references().len(), 2);
+        assert_eq!(
+            bib.dangling_references()[0].to_string(),
+            "entry `first` refers to unknown entry `conf` in its `crossref` field"
+        );
+        bib.merge(Bibliography::from_bib_str(parent)?, DuplicatePolicy::Error)?;
+        assert!(bib.dangling_references().is_empty());
+        let entry = bib.get_entry("second").unwrap();
+        assert_eq!(entry.fields["booktitle"], "Proceedings of the Conference");
+        Ok(())
+    }
+
+    #[test]
+    fn citations_with_multiple_keys_and_notes() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{a, author = {Jane Doe}, title = {Foo}, year = 2020}
+            @misc{b, author = {John Smith}, title = {Bar}, year = 2021}",
+        )?;
+        let expand = |text: &str| {
+            bib.expand_citations_in_string(
+                text,
+                ReferenceStyle::APA,
+                Format::Plain,
+                CitationSyntax::Latex,
+                false,
+                SortOrder::Style,
+                false,
+            )
+        };
+        assert_eq!(
+            expand("As shown in \\cite{a,b}")?,
+            "As shown in Doe, J. (2020). Foo.; Smith, J. (2021). Bar."
+        );
+        assert_eq!(
+            expand("\\cite[see][p.~5]{a}")?,
+            "see Doe, J. (2020). Foo, p.\u{a0}5."
+        );
+        assert_eq!(expand("\\cite[ch. 2]{b}")?, "Smith, J. (2021). Bar, ch. 2.");
+        assert_eq!(
+            expand("\\cite[p.~5]{a,missing}")?,
+            "\\cite[p.~5]{a,missing}"
+        );
+        assert!(bib
+            .expand_citations_in_string(
+                "\\cite{a,missing}",
+                ReferenceStyle::APA,
+                Format::Plain,
+                CitationSyntax::Latex,
+                false,
+                SortOrder::Style,
+                true
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn citation_commands() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{a, author = {Jane Doe}, title = {Foo}, year = 2020}
+            @misc{b, author = {John Smith and Ann Jones}, title = {Bar}, year = 2021}
+            @misc{c, author = {Jane Doe and John Smith and Ann Jones}, title = {Baz}}",
+        )?;
+        let expand = |text: &str, style: ReferenceStyle| {
+            bib.expand_citations_in_string(
+                text,
+                style,
+                Format::Plain,
+                CitationSyntax::Latex,
+                false,
+                SortOrder::Style,
+                false,
+            )
+        };
+        let text = "\\citet{b} showed \\citep[see][p.~5]{a,c}. \\citeauthor{c} in \\citeyear{a} \\parencite{b}.\\nocite{*}";
+        assert_eq!(
+            expand(text, ReferenceStyle::APA)?,
+            "Smith and Jones (2021) showed (see Doe, 2020; Doe et al., n.d., p.\u{a0}5). Doe et al. in 2020 (Smith & Jones, 2021)."
+        );
+        assert_eq!(
+            expand(text, ReferenceStyle::IEEE)?,
+            "Smith and Jones [1] showed see [2], [3, p.\u{a0}5]. Doe et al. in 2020 [1]."
+        );
+        assert_eq!(
+            expand("\\textcite[p.~5]{a}", ReferenceStyle::APA)?,
+            "Doe (2020, p.\u{a0}5)"
+        );
+        assert_eq!(
+            expand("\\citep{missing}", ReferenceStyle::APA)?,
+            "\\citep{missing}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pandoc_citations() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{a, author = {Jane Doe}, title = {Foo}, year = 2020}
+            @misc{b, author = {John Smith and Ann Jones}, title = {Bar}, year = 2021}",
+        )?;
+        let expand = |text: &str, style: ReferenceStyle| {
+            bib.expand_citations_in_string(
+                text,
+                style,
+                Format::Plain,
+                CitationSyntax::Pandoc,
+                false,
+                SortOrder::Style,
+                false,
+            )
+        };
+        let text = "@b showed it [see @a; @b, p. 5], as did Doe [-@a]. Mail me@x.org [@missing].";
+        assert_eq!(
+            expand(text, ReferenceStyle::APA)?,
+            "Smith and Jones (2021) showed it (see Doe, 2020; Smith & Jones, 2021, p. 5), as did Doe (2020). Mail me@x.org [@missing]."
+        );
+        assert_eq!(
+            expand(text, ReferenceStyle::IEEE)?,
+            "Smith and Jones [1] showed it see [2], [1, p. 5], as did Doe [2]. Mail me@x.org [@missing]."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn markers_and_reference_list() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{a, author = {Jane Doe}, title = {Foo}, year = 2020}
+            @misc{b, author = {Ann Able}, title = {Bar}, year = 2021}
+            @misc{c, author = {Zed Zorn}, title = {Baz}, year = 2019}
+            @misc{d, author = {Bob Brown}, title = {Qux}, year = 2018}",
+        )?;
+        let expand = |text: &str, style: ReferenceStyle| {
+            bib.expand_citations_in_string(
+                text,
+                style,
+                Format::Plain,
+                CitationSyntax::Latex,
+                true,
+                SortOrder::Style,
+                false,
+            )
+        };
+        let text = "\\cite{a} and \\citet{b}, again \\cite{a}.\\nocite{c}\n\n\\printbibliography\n";
+        assert_eq!(
+            expand(text, ReferenceStyle::IEEE)?,
+            "[1] and Able [2], again [1].\n\n[1] J. Doe, Foo, 2020.\n\n[2] A. Able, Bar, 2021.\n\n[3] Z. Zorn, Baz, 2019.\n"
+        );
+        assert_eq!(
+            expand(text, ReferenceStyle::APA)?,
+            "(Doe, 2020) and Able (2021), again (Doe, 2020).\n\nAble, A. (2021). Bar.\n\nDoe, J. (2020). Foo.\n\nZorn, Z. (2019). Baz.\n"
+        );
+        // without a placeholder the list goes at the end
+        assert_eq!(
+            expand("as in \\cite{d}", ReferenceStyle::IEEE)?,
+            "as in [1]\n\n[1] B. Brown, Qux, 2018.\n"
+        );
+        assert_eq!(
+            expand("nothing cited\n", ReferenceStyle::IEEE)?,
+            "nothing cited\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn entries_without_authors_are_told_apart_by_title() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{one, title = {Alpha}}
+            @misc{two, title = {Beta}}
+            @misc{three, title = {Gamma}}",
+        )?;
+        assert_eq!(
+            bib.expand_citations_in_string(
+                "\\cite{one,two,three}\n\\printbibliography",
+                ReferenceStyle::APA,
+                Format::Plain,
+                CitationSyntax::Latex,
+                true,
+                SortOrder::Style,
+                true,
+            )?,
+            "(Alpha, n.d.; Beta, n.d.; Gamma, n.d.)\n(n.d.). Alpha.\n\n(n.d.). Beta.\n\n(n.d.). Gamma."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn author_year_disambiguation() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{forests, author = {Leo Breiman}, title = {Random forests}, year = 2001}
+            @misc{cultures, author = {Leo Breiman}, title = {Statistical modeling: the two cultures}, year = 2001}
+            @misc{bagging, author = {Leo Breiman}, title = {Bagging predictors}, year = 1996}
+            @misc{jie, author = {Jie Wang}, title = {Foo}, year = 2020}
+            @misc{jun, author = {Jun Wang}, title = {Bar}, year = 2019}
+            @misc{michael, author = {Michael Wang}, title = {Baz}, year = 2018}",
+        )?;
+        let expand = |text: &str, style: ReferenceStyle| {
+            bib.expand_citations_in_string(
+                text,
+                style,
+                Format::Plain,
+                CitationSyntax::Latex,
+                true,
+                SortOrder::Style,
+                false,
+            )
+        };
+        assert_eq!(
+            expand(
+                "\\cite{forests,bagging} \\citet{cultures}\n\\printbibliography",
+                ReferenceStyle::APA
+            )?,
+            "(Breiman, 2001a; Breiman, 1996) Breiman (2001b)\nBreiman, L. (1996). Bagging predictors.\n\nBreiman, L. (2001a). Random forests.\n\nBreiman, L. (2001b). Statistical modeling: The two cultures."
+        );
+        assert_eq!(
+            expand("\\cite{jie,jun,michael}\\printbibliography", ReferenceStyle::APA)?,
+            "(Jie Wang, 2020; Jun Wang, 2019; M. Wang, 2018)Wang, J. (2019). Bar.\n\nWang, J. (2020). Foo.\n\nWang, M. (2018). Baz."
+        );
+        // without other Wangs no initials are needed
+        assert_eq!(
+            expand("\\cite{michael}\\printbibliography", ReferenceStyle::APA)?,
+            "(Wang, 2018)Wang, M. (2018). Baz."
+        );
+        // numbered styles don't need any of it
+        assert_eq!(
+            expand("\\cite{forests,cultures}\\printbibliography", ReferenceStyle::IEEE)?,
+            "[1], [2][1] L. Breiman, Random Forests, 2001.\n\n[2] L. Breiman, Statistical Modeling: The Two Cultures, 2001."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reference_lists_are_disambiguated() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{forests, author = {Leo Breiman}, title = {Random forests}, year = 2001}
+            @misc{cultures, author = {Leo Breiman}, title = {Statistical modeling: the two cultures}, year = 2001}",
+        )?;
+        let expected = vec![
+            "Breiman, L. (2001a). Random forests.",
+            "Breiman, L. (2001b). Statistical modeling: The two cultures.",
+        ];
+        assert_eq!(
+            bib.fmt_entries(ReferenceStyle::APA, Format::Plain, SortOrder::Style),
+            expected
+        );
+        let (formatted, _) = bib.fmt_entries_filtered(
+            ReferenceStyle::APA,
+            Format::Plain,
+            vec!["cultures".to_string(), "forests".to_string()],
+            SortOrder::Style,
+        )?;
+        assert_eq!(formatted, expected);
+        assert_eq!(
+            bib.expand_citations_in_string(
+                "\\cite{forests} and \\cite{cultures}",
+                ReferenceStyle::APA,
+                Format::Plain,
+                CitationSyntax::Latex,
+                false,
+                SortOrder::Style,
+                true,
+            )?,
+            expected.join(" and ")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sort_orders() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{zorn, author = {Zed Zorn}, title = {Baz}, year = 2019}
+            @misc{able, author = {Ann Able}, title = {Bar}, year = 2021}
+            @misc{doe, author = {Jane Doe}, title = {Foo}, year = 2020}",
+        )?;
+        let first = |style: ReferenceStyle, order: SortOrder| {
+            let text = "\\cite{doe} \\cite{zorn} \\cite{able}";
+            let (formatted, _) = Bibliography::from(bib.entries.clone()).fmt_entries_filtered(
+                style,
+                Format::Plain,
+                vec!["doe".to_string(), "zorn".to_string(), "able".to_string()],
+                order,
+            )?;
+            let expanded = bib.expand_citations_in_string(
+                text,
+                style,
+                Format::Plain,
+                CitationSyntax::Latex,
+                true,
+                order,
+                true,
+            )?;
+            Ok::<_, anyhow::Error>((formatted[0].clone(), expanded))
+        };
+        assert_eq!(
+            first(ReferenceStyle::APA, SortOrder::Style)?.0,
+            "Able, A. (2021). Bar."
+        );
+        assert_eq!(
+            first(ReferenceStyle::APA, SortOrder::Year)?.0,
+            "Zorn, Z. (2019). Baz."
+        );
+        assert_eq!(
+            first(ReferenceStyle::APA, SortOrder::None)?.0,
+            "Doe, J. (2020). Foo."
+        );
+        assert_eq!(
+            first(ReferenceStyle::IEEE, SortOrder::Style)?.1,
+            "[1] [2] [3]\n\n[1] J. Doe, Foo, 2020.\n\n[2] Z. Zorn, Baz, 2019.\n\n[3] A. Able, Bar, 2021.\n"
+        );
+        // the numbers follow the order of the reference list
+        assert_eq!(
+            first(ReferenceStyle::IEEE, SortOrder::Key)?.1,
+            "[2] [3] [1]\n\n[1] A. Able, Bar, 2021.\n\n[2] J. Doe, Foo, 2020.\n\n[3] Z. Zorn, Baz, 2019.\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_key_policies() -> Result<()> {
+        let first = "@misc{a, title = {First}, year = 2020}\n\n@misc{b, title = {Other}}";
+        let second = "@misc{c, title = {Third}}\n@misc{a, title = {Second}, note = {Extra}}";
+        let merged = |policy: DuplicatePolicy| -> Result<Bibliography> {
+            let mut bib = Bibliography::from_bib_str(first)?;
+            bib.merge(Bibliography::from_bib_str(second)?, policy)?;
+            Ok(bib)
+        };
+
+        let err = merged(DuplicatePolicy::Error).err().unwrap();
+        assert_eq!(err.to_string(), "duplicate key `a` at line 1 and line 2");
+
+        let bib = merged(DuplicatePolicy::FirstWins)?;
+        let entry = bib.get_entry("a").unwrap();
+        assert_eq!(entry.fields["title"], "First");
+        assert!(!entry.fields.contains_key("note"));
+        assert_eq!(bib.duplicates().len(), 1);
+        assert_eq!(bib.entries.len(), 3);
+
+        let bib = merged(DuplicatePolicy::LastWins)?;
+        let entry = bib.get_entry("a").unwrap();
+        assert_eq!(entry.fields["title"], "Second");
+        assert!(!entry.fields.contains_key("year"));
+
+        let bib = merged(DuplicatePolicy::Merge)?;
+        let entry = bib.get_entry("a").unwrap();
+        assert_eq!(entry.fields["title"], "First");
+        assert_eq!(entry.fields["year"], "2020");
+        assert_eq!(entry.fields["note"], "Extra");
+        Ok(())
+    }
+
+    #[test]
+    fn children_inherit_from_the_parent_that_is_kept() -> Result<()> {
+        let first = "@inproceedings{paper, title = {Paper}, crossref = {conf}}
+            @proceedings{conf, title = {Old}, year = 2020}";
+        let second = "@proceedings{conf, title = {New}, publisher = {ACM}}";
+        let merged = |policy: DuplicatePolicy| -> Result<Bibliography> {
+            let mut bib = Bibliography::from_bib_str(first)?;
+            bib.merge(Bibliography::from_bib_str(second)?, policy)?;
+            Ok(bib)
+        };
+
+        let bib = merged(DuplicatePolicy::LastWins)?;
+        let paper = bib.get_entry("paper").unwrap();
+        assert_eq!(paper.fields["booktitle"], "New");
+        assert_eq!(paper.fields["publisher"], "ACM");
+        assert!(!paper.fields.contains_key("year"));
+
+        let bib = merged(DuplicatePolicy::Merge)?;
+        let paper = bib.get_entry("paper").unwrap();
+        assert_eq!(paper.fields["booktitle"], "Old");
+        assert_eq!(paper.fields["year"], "2020");
+        assert_eq!(paper.fields["publisher"], "ACM");
+        Ok(())
+    }
+
+    #[test]
+    fn duplicates_across_files() -> Result<()> {
+        let path = |name: &str, contents: &str| -> Result<PathBuf> {
+            let path = std::env::temp_dir().join(name);
+            File::create(&path)?.write_all(contents.as_bytes())?;
+            Ok(path)
+        };
+        let first = path("duplicates_first.bib", "@misc{a, title = {First}}")?;
+        let second = path(
+            "duplicates_second.bib",
+            "@misc{b, title = {Other}}\n\n@misc{a, title = {Second}}\n@misc{b, title = {Again}}",
+        )?;
+        let err =
+            Bibliography::from_files(vec![first.clone(), second.clone()], DuplicatePolicy::Error)
+                .err()
+                .unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "duplicate key `a` at {}:1 and {}:3",
+                first.display(),
+                second.display()
+            )
+        );
+        // keys repeated within a file are duplicates as well
+        let bib = Bibliography::from_files(vec![first, second.clone()], DuplicatePolicy::LastWins)?;
+        assert_eq!(bib.duplicates().len(), 2);
+        assert_eq!(
+            bib.duplicates()[1].to_string(),
+            format!("duplicate key `b` at {0}:1 and {0}:4", second.display())
+        );
+        assert_eq!(bib.get_entry("b").unwrap().fields["title"], "Again");
+        Ok(())
+    }
+
+    #[test]
+    fn remove_semantic_duplicates() -> Result<()> {
+        let contents = "@string{ml = {Machine Learning}}
+
+@article{short, title = {Random forests}, doi = {10.1023/a:1010933404324}}
+
+@article{breiman2001,
+  author = {Leo Breiman},
+  title = {Random Forests},
+  journal = ml,
+  doi = {10.1023/A:1010933404324},
+}
+
+@misc{other, title = {Other}}
+";
+        let path = std::env::temp_dir().join("semantic_duplicates.bib");
+        File::create(&path)?.write_all(contents.as_bytes())?;
+        let bib = Bibliography::from_files(vec![path.clone()], DuplicatePolicy::Error)?;
+        let clusters = bib.find_duplicates();
+        // the entry with the most fields is kept
+        assert_eq!(clusters, vec![vec!["breiman2001", "short"]]);
+        assert_eq!(bib.source("short").unwrap().line, 3);
+        assert_eq!(
+            bib.without_duplicates(&path, contents, &clusters),
+            "@string{ml = {Machine Learning}}
+
+@article{breiman2001,
+  author = {Leo Breiman},
+  title = {Random Forests},
+  journal = ml,
+  doi = {10.1023/A:1010933404324},
+}
+
+@misc{other, title = {Other}}
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove_repeated_keys() -> Result<()> {
+        let first = std::env::temp_dir().join("repeated_keys_first.bib");
+        File::create(&first)?.write_all(b"@misc{a, title = {First}}\n")?;
+        let second = std::env::temp_dir().join("repeated_keys_second.bib");
+        let contents =
+            "@misc{a, title = {Second}}\n\n@misc{b, title = {B}}\n\n@misc{b, title = {B}}\n";
+        File::create(&second)?.write_all(contents.as_bytes())?;
+        let bib = Bibliography::from_files(
+            vec![first.clone(), second.clone()],
+            DuplicatePolicy::LastWins,
+        )?;
+        let clusters = bib.find_duplicates();
+        assert!(clusters.is_empty());
+        assert_eq!(
+            bib.without_duplicates(&first, "@misc{a, title = {First}}\n", &clusters),
+            ""
+        );
+        // the last of the two identical entries is the one that is kept
+        assert_eq!(bib.source("b").unwrap().line, 5);
+        assert_eq!(
+            bib.without_duplicates(&second, contents, &clusters),
+            "@misc{a, title = {Second}}\n\n@misc{b, title = {B}}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn entries_are_indexed_by_key() -> Result<()> {
+        let mut bib = Bibliography::from_bib_str(
+            "@misc{b, title = {B}}\n@misc{a, title = {A}}\n@misc{b, title = {Again}}",
+        )?;
+        // within a single bib string the first of a repeated key is kept
+        assert_eq!(bib.get_entry("b").unwrap().fields["title"], "B");
+        assert_eq!(bib.duplicates().len(), 1);
+        assert_eq!(bib.duplicates()[0].second.line, 3);
+        assert!(bib.get_entry("c").is_none());
+
+        bib.merge(
+            Bibliography::from_bib_str("@misc{c, title = {C}}\n@misc{a, title = {New A}}")?,
+            DuplicatePolicy::LastWins,
+        )?;
+        let keys: Vec<&str> = bib.entries().iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["b", "a", "c"]);
+        assert_eq!(bib.get_entry("a").unwrap().fields["title"], "New A");
+        assert_eq!(bib.get_entry("c").unwrap().fields["title"], "C");
+        assert_eq!(bib.source("c").unwrap().line, 1);
+        assert_eq!(bib.source("a").unwrap().line, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn policy_applies_within_one_file() -> Result<()> {
+        let path = std::env::temp_dir().join("duplicate_parents.bib");
+        File::create(&path)?.write_all(
+            b"@book{conf, title = {First}}\n@book{conf, title = {Second}}\n@inproceedings{paper, title = {A paper}, crossref = {conf}}",
+        )?;
+        let booktitle = |policy: DuplicatePolicy| -> Result<String> {
+            let bib = Bibliography::from_files(vec![path.clone()], policy)?;
+            assert_eq!(bib.entries().len(), 2);
+            Ok(bib.get_entry("paper").unwrap().fields["booktitle"].clone())
+        };
+        assert_eq!(booktitle(DuplicatePolicy::FirstWins)?, "First");
+        assert_eq!(booktitle(DuplicatePolicy::LastWins)?, "Second");
+        assert!(Bibliography::from_files(vec![path.clone()], DuplicatePolicy::Error).is_err());
+        Ok(())
+    }
 }
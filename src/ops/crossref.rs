@@ -0,0 +1,289 @@
+use std::{collections::HashMap, fmt};
+
+use crate::parsing::entry::{BibEntry, EntryType};
+
+/// Fields that belong to the entry itself and are never inherited
+const NOT_INHERITED: &[&str] = &[
+    "ids",
+    "crossref",
+    "xref",
+    "xdata",
+    "entryset",
+    "entrysubtype",
+    "execute",
+    "label",
+    "options",
+    "presort",
+    "related",
+    "relatedoptions",
+    "relatedstring",
+    "relatedtype",
+    "shorthand",
+    "shorthandintro",
+    "sortkey",
+];
+
+/// A `crossref` or `xdata` field that refers to an entry that doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    /// the key of the entry with the reference
+    pub key: String,
+    /// `crossref` or `xdata`
+    pub field: String,
+    /// the key that was referred to
+    pub target: String,
+}
+
+impl fmt::Display for DanglingReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "entry `{}` refers to unknown entry `{}` in its `{}` field",
+            self.key, self.target, self.field
+        )
+    }
+}
+
+/// `@xdata` entries only hold data for other entries and aren't cited themselves
+pub fn is_xdata(entry: &BibEntry) -> bool {
+    entry.kind == EntryType::Other("xdata".to_string())
+}
+
+/// The fields of a parent that are inherited under a different name, e.g. the title of
+/// a proceedings is the booktitle of the papers in it.
+fn field_mapping(parent: &EntryType, child: &EntryType) -> &'static [(&'static str, &'static str)] {
+    use EntryType::*;
+    match (parent, child) {
+        (
+            Book | Mvbook | Collection | Proceedings,
+            Inbook | Incollection | Inproceedings | Conference,
+        ) => &[
+            ("title", "booktitle"),
+            ("subtitle", "booksubtitle"),
+            ("titleaddon", "booktitleaddon"),
+        ],
+        (Mvbook, Book | Collection | Proceedings | Mvbook) => &[
+            ("title", "maintitle"),
+            ("subtitle", "mainsubtitle"),
+            ("titleaddon", "maintitleaddon"),
+        ],
+        (Periodical, Article) => &[("title", "journal"), ("subtitle", "journalsubtitle")],
+        _ => &[],
+    }
+}
+
+fn inherit(child: &mut BibEntry, parent: &BibEntry, mapping: &[(&str, &str)]) {
+    for (name, value) in &parent.fields {
+        if NOT_INHERITED.contains(&name.as_str()) {
+            continue;
+        }
+        let target = mapping
+            .iter()
+            .find(|(from, _)| from == name)
+            .map_or(name.as_str(), |(_, to)| to);
+        // the fields of the child always take precedence
+        child
+            .fields
+            .entry(target.to_string())
+            .or_insert_with(|| value.clone());
+    }
+    if child.authors.is_empty() {
+        child.authors = parent.authors.clone();
+    }
+    for (name, list) in &parent.names {
+        child
+            .names
+            .entry(name.clone())
+            .or_insert_with(|| list.clone());
+    }
+}
+
+/// The keys an entry inherits from, `xdata` before `crossref`, together with the field they are in
+pub(crate) fn parent_keys(entry: &BibEntry) -> Vec<(&'static str, String)> {
+    let mut keys: Vec<(&'static str, String)> = entry
+        .fields
+        .get("xdata")
+        .map(|x| {
+            x.split(',')
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(|k| ("xdata", k.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(c) = entry.fields.get("crossref") {
+        keys.push(("crossref", c.trim().to_string()));
+    }
+    keys
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Unresolved,
+    InProgress,
+    Resolved,
+}
+
+struct Resolver<'a> {
+    entries: &'a mut [BibEntry],
+    index: &'a HashMap<String, usize>,
+    state: Vec<State>,
+    dangling: Vec<DanglingReference>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, i: usize) {
+        if self.state[i] != State::Unresolved {
+            // either done already, or we're in a cycle which we just break here
+            return;
+        }
+        self.state[i] = State::InProgress;
+        for (field, key) in parent_keys(&self.entries[i]) {
+            match self.index.get(&key).copied() {
+                Some(p) if p != i => {
+                    // parents can inherit from their own parents
+                    self.resolve(p);
+                    let parent = self.entries[p].clone();
+                    let mapping = match field {
+                        "crossref" => field_mapping(&parent.kind, &self.entries[i].kind),
+                        _ => &[],
+                    };
+                    inherit(&mut self.entries[i], &parent, mapping);
+                }
+                Some(_) => (),
+                None => self.dangling.push(DanglingReference {
+                    key: self.entries[i].key.clone(),
+                    field: field.to_string(),
+                    target: key,
+                }),
+            }
+        }
+        self.state[i] = State::Resolved;
+    }
+}
+
+/// Fill in the missing fields of entries with a `crossref` or `xdata` field from the
+/// entries they refer to, following the biblatex inheritance rules. Fields the entry
+/// already has are never overwritten, so this can safely be run more than once. Parents
+/// are looked up by their position in `index`, which is the one of the bibliography.
+/// Returns the references to entries that don't exist.
+pub fn resolve_inheritance(
+    entries: &mut [BibEntry],
+    index: &HashMap<String, usize>,
+) -> Vec<DanglingReference> {
+    let mut resolver = Resolver {
+        state: vec![State::Unresolved; entries.len()],
+        entries,
+        index,
+        dangling: Vec::new(),
+    };
+    for i in 0..resolver.entries.len() {
+        resolver.resolve(i);
+    }
+    resolver.dangling
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parsing::entry::{parse_bib, BibItem};
+    use anyhow::Result;
+
+    fn entries(contents: &str) -> Result<Vec<BibEntry>> {
+        parse_bib(contents)?
+            .into_iter()
+            .filter_map(|item| match item {
+                BibItem::Entry(components) => Some(BibEntry::try_from(components)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn index_of(entries: &[BibEntry]) -> HashMap<String, usize> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.key.clone(), i))
+            .collect()
+    }
+
+    #[test]
+    fn crossref_inherits_missing_fields() -> Result<()> {
+        let mut entries = entries(
+            r"@inproceedings{paper,
+                author = {Jane Doe},
+                title = {A paper},
+                pages = {1--10},
+                crossref = {conf},
+            }
+            @proceedings{conf,
+                editor = {John Smith},
+                title = {Proceedings of the conference},
+                publisher = {Springer},
+                year = 2020,
+                pages = {1--300},
+            }",
+        )?;
+        let index = index_of(&entries);
+        assert!(resolve_inheritance(&mut entries, &index).is_empty());
+        let paper = &entries[0];
+        assert_eq!(paper.fields["title"], "A paper");
+        assert_eq!(paper.fields["booktitle"], "Proceedings of the conference");
+        assert_eq!(paper.names["editor"][0].last, vec!["Smith"]);
+        assert_eq!(paper.fields["year"], "2020");
+        assert_eq!(paper.fields["pages"], "1–10");
+        assert_eq!(paper.authors[0].last, vec!["Doe"]);
+        Ok(())
+    }
+
+    #[test]
+    fn xdata_and_chains() -> Result<()> {
+        let mut entries = entries(
+            r"@inproceedings{paper,
+                title = {A paper},
+                crossref = {conf},
+            }
+            @proceedings{conf,
+                title = {Proceedings},
+                xdata = {data},
+            }
+            @xdata{data,
+                publisher = {Springer},
+                year = 2020,
+            }",
+        )?;
+        let index = index_of(&entries);
+        assert!(resolve_inheritance(&mut entries, &index).is_empty());
+        assert_eq!(entries[0].fields["publisher"], "Springer");
+        assert_eq!(entries[1].fields["year"], "2020");
+        assert!(is_xdata(&entries[2]));
+        Ok(())
+    }
+
+    #[test]
+    fn dangling_references_and_cycles() -> Result<()> {
+        let mut entries = entries(
+            r"@misc{paper, title = {A}, crossref = {other}}
+            @misc{other, note = {B}, crossref = {paper}, xdata = {missing, data}}
+            @xdata{data, year = 2020}",
+        )?;
+        let index = index_of(&entries);
+        let dangling = resolve_inheritance(&mut entries, &index);
+        assert_eq!(
+            dangling,
+            vec![DanglingReference {
+                key: "other".to_string(),
+                field: "xdata".to_string(),
+                target: "missing".to_string(),
+            }]
+        );
+        assert_eq!(
+            dangling[0].to_string(),
+            "entry `other` refers to unknown entry `missing` in its `xdata` field"
+        );
+        assert_eq!(entries[0].fields["note"], "B");
+        assert_eq!(entries[0].fields["year"], "2020");
+        assert_eq!(entries[0].fields["crossref"], "other");
+        Ok(())
+    }
+}
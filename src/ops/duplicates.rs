@@ -0,0 +1,381 @@
+use anyhow::anyhow;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::PathBuf,
+};
+
+use crate::{
+    ops::crossref::parent_keys,
+    parsing::{date::EntryDate, entry::BibEntry, names::NameKind},
+};
+
+/// What to do with an entry that has the same key as one that was loaded before
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DuplicatePolicy {
+    /// fail to load the bibliography
+    Error,
+    /// keep the entry that was loaded first, like biber
+    #[default]
+    FirstWins,
+    /// replace the entry that was loaded first
+    LastWins,
+    /// keep the entry that was loaded first, adding the fields it doesn't have
+    Merge,
+}
+
+impl TryFrom<&str> for DuplicatePolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, anyhow::Error> {
+        match value.to_lowercase().as_str() {
+            "error" => Ok(DuplicatePolicy::Error),
+            "first-wins" => Ok(DuplicatePolicy::FirstWins),
+            "last-wins" => Ok(DuplicatePolicy::LastWins),
+            "merge" => Ok(DuplicatePolicy::Merge),
+            _ => Err(anyhow!("invalid duplicate policy")),
+        }
+    }
+}
+
+/// Where an entry was defined
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EntrySource {
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    /// the entry as it was written
+    pub text: String,
+}
+
+impl fmt::Display for EntrySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file.display(), self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// Two entries with the same key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey {
+    pub key: String,
+    /// where the entry that was loaded first was defined
+    pub first: EntrySource,
+    /// where the entry with the same key was defined
+    pub second: EntrySource,
+}
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "duplicate key `{}` at {} and {}",
+            self.key, self.first, self.second
+        )
+    }
+}
+
+/// Add the fields and names of `other` that `kept` doesn't have to it
+pub fn merge_fields(kept: &mut BibEntry, other: BibEntry) {
+    for (name, value) in other.fields {
+        kept.fields.entry(name).or_insert(value);
+    }
+    if kept.authors.is_empty() {
+        kept.authors = other.authors;
+    }
+    for (name, list) in other.names {
+        kept.names.entry(name).or_insert(list);
+    }
+}
+
+/// How similar two normalised titles have to be to count as the same
+const TITLE_SIMILARITY: f64 = 0.9;
+
+/// A DOI without the resolver or `doi:` in front of it, in lowercase
+fn normalise_doi(doi: &str) -> Option<String> {
+    let doi = doi.trim().to_lowercase();
+    doi.find("10.").map(|start| doi[start..].to_string())
+}
+
+/// An ISBN as its 13 digits, without the hyphens and spaces
+fn normalise_isbn(isbn: &str) -> Option<String> {
+    let digits: String = isbn
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == 'x' || *c == 'X')
+        .collect::<String>()
+        .to_uppercase();
+    match digits.len() {
+        13 => Some(digits),
+        // the old ISBN-10s are ISBN-13s with 978 in front and a different check digit
+        10 => {
+            let mut isbn13 = format!("978{}", &digits[..9]);
+            let sum: u32 = isbn13
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .enumerate()
+                .map(|(i, d)| if i % 2 == 0 { d } else { 3 * d })
+                .sum();
+            isbn13.push_str(&((10 - sum % 10) % 10).to_string());
+            Some(isbn13)
+        }
+        _ => None,
+    }
+}
+
+/// The arXiv id of an entry without its version, from an arXiv `eprint` or an arxiv.org url
+fn arxiv_id(entry: &BibEntry) -> Option<String> {
+    let is_arxiv = |field: &str| {
+        entry
+            .fields
+            .get(field)
+            .is_some_and(|t| t.eq_ignore_ascii_case("arxiv"))
+    };
+    let id = match entry.fields.get("eprint") {
+        Some(eprint) if is_arxiv("eprinttype") || is_arxiv("archiveprefix") => eprint.clone(),
+        _ => {
+            let url = entry.fields.get("url")?;
+            let start = url.find("arxiv.org/abs/")? + "arxiv.org/abs/".len();
+            url[start..].to_string()
+        }
+    };
+    let id = id.trim().to_lowercase();
+    let id = id.strip_prefix("arxiv:").unwrap_or(&id);
+    let without_version = match id.rfind('v') {
+        Some(v) if v > 0 && id[v + 1..].chars().all(|c| c.is_ascii_digit()) => &id[..v],
+        _ => id,
+    };
+    Some(without_version.to_string())
+}
+
+/// A title with only its lowercase letters and digits, so differences in case,
+/// punctuation and braces don't matter
+fn normalise_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How similar two strings are, from 0 for nothing in common to 1 for equal, based on
+/// their levenshtein distance
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / a.len().max(b.len()) as f64
+}
+
+/// The surname of the first author (or editor) and the year of an entry, which entries
+/// have to share before their titles are compared
+fn author_and_year(entry: &BibEntry) -> (String, Option<i32>) {
+    let names = match entry.authors.is_empty() {
+        true => entry.names.get("editor").cloned().unwrap_or_default(),
+        false => entry.authors.clone(),
+    };
+    let surname = names
+        .iter()
+        .find(|n| n.kind != NameKind::Others)
+        .map(|n| normalise_title(&n.last.join(" ")))
+        .unwrap_or_default();
+    let year = EntryDate::from_fields(&entry.fields).and_then(|d| d.year());
+    (surname, year)
+}
+
+/// the representative of the cluster `i` is in
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    // the earliest entry represents the cluster
+    parents[a.max(b)] = a.min(b);
+}
+
+/// Groups of entries that are probably the same work under different keys: they have
+/// the same DOI, ISBN or arXiv id, or the same first author, year and (nearly) the same
+/// title. Returns the indices of the entries in each group of two or more.
+pub fn find_duplicates(entries: &[BibEntry]) -> Vec<Vec<usize>> {
+    let mut parents: Vec<usize> = (0..entries.len()).collect();
+    let mut identifiers: BTreeMap<(&str, String), usize> = BTreeMap::new();
+    let mut same_author_and_year: BTreeMap<(String, Option<i32>), Vec<usize>> = BTreeMap::new();
+    let by_key: HashMap<&str, &BibEntry> = entries.iter().map(|e| (e.key.as_str(), e)).collect();
+    for (i, entry) in entries.iter().enumerate() {
+        // papers in the same proceedings inherit its ISBN, which doesn't make them the same
+        let inherited_from: Vec<&BibEntry> = parent_keys(entry)
+            .iter()
+            .filter_map(|(_, key)| by_key.get(key.as_str()).copied())
+            .collect();
+        let own = |field: &str| {
+            entry.fields.get(field).filter(|v| {
+                !inherited_from
+                    .iter()
+                    .any(|p| p.fields.get(field) == Some(v))
+            })
+        };
+        let ids = [
+            ("doi", own("doi").and_then(|d| normalise_doi(d))),
+            ("isbn", own("isbn").and_then(|d| normalise_isbn(d))),
+            ("arxiv", arxiv_id(entry)),
+        ];
+        for (kind, id) in ids {
+            if let Some(id) = id {
+                match identifiers.get(&(kind, id.clone())) {
+                    Some(j) => union(&mut parents, *j, i),
+                    None => {
+                        identifiers.insert((kind, id), i);
+                    }
+                }
+            }
+        }
+        let (surname, year) = author_and_year(entry);
+        if !surname.is_empty() && year.is_some() {
+            same_author_and_year
+                .entry((surname, year))
+                .or_default()
+                .push(i);
+        }
+    }
+    for group in same_author_and_year.values() {
+        let titles: Vec<String> = group
+            .iter()
+            .map(|i| normalise_title(entries[*i].fields.get("title").map_or("", |t| t)))
+            .collect();
+        for (a, title_a) in titles.iter().enumerate() {
+            for (b, title_b) in titles.iter().enumerate().skip(a + 1) {
+                if !title_a.is_empty() && similarity(title_a, title_b) >= TITLE_SIMILARITY {
+                    union(&mut parents, group[a], group[b]);
+                }
+            }
+        }
+    }
+
+    let mut clusters: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..entries.len() {
+        let root = find(&mut parents, i);
+        clusters.entry(root).or_default().push(i);
+    }
+    clusters.into_values().filter(|c| c.len() > 1).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::bibligraphy::Bibliography;
+    use anyhow::Result;
+
+    #[test]
+    fn display_duplicate() {
+        let duplicate = DuplicateKey {
+            key: "foo".to_string(),
+            first: EntrySource {
+                file: Some(PathBuf::from("a.bib")),
+                line: 3,
+                text: String::new(),
+            },
+            second: EntrySource {
+                file: None,
+                line: 12,
+                text: String::new(),
+            },
+        };
+        assert_eq!(
+            duplicate.to_string(),
+            "duplicate key `foo` at a.bib:3 and line 12"
+        );
+    }
+
+    #[test]
+    fn fields_of_the_kept_entry_take_precedence() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{a, title = {Foo}, year = 2020}
+            @misc{b, author = {Jane Doe}, title = {Bar}, doi = {10.1/x}}",
+        )?;
+        let mut kept = bib.get_entry("a").unwrap().clone();
+        merge_fields(&mut kept, bib.get_entry("b").unwrap().clone());
+        assert_eq!(kept.fields["title"], "Foo");
+        assert_eq!(kept.fields["year"], "2020");
+        assert_eq!(kept.fields["doi"], "10.1/x");
+        assert_eq!(kept.authors[0].last, vec!["Doe"]);
+        Ok(())
+    }
+
+    #[test]
+    fn identifiers_are_normalised() {
+        assert_eq!(
+            normalise_doi("https://doi.org/10.1023/A:1010933404324"),
+            Some("10.1023/a:1010933404324".to_string())
+        );
+        assert_eq!(normalise_doi("doi:10.1/X"), normalise_doi("10.1/x"));
+        assert_eq!(
+            normalise_isbn("0-306-40615-2"),
+            Some("9780306406157".to_string())
+        );
+        assert_eq!(normalise_isbn("12-34"), None);
+        assert_eq!(normalise_title("{Random} Forests."), "random forests");
+    }
+
+    #[test]
+    fn arxiv_ids() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{a, eprint = {1706.03762v5}, eprinttype = {arxiv}}
+            @misc{b, eprint = {arXiv:1706.03762}, archiveprefix = {arXiv}}
+            @misc{c, url = {https://arxiv.org/abs/1706.03762}}
+            @misc{d, eprint = {1706.03762}}",
+        )?;
+        let id = |key: &str| arxiv_id(bib.get_entry(key).unwrap());
+        assert_eq!(id("a"), Some("1706.03762".to_string()));
+        assert_eq!(id("b"), id("a"));
+        assert_eq!(id("c"), id("a"));
+        assert_eq!(id("d"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn title_similarity() {
+        assert_eq!(similarity("random forests", "random forests"), 1.0);
+        assert!(similarity("random forest", "random forests") >= TITLE_SIMILARITY);
+        assert!(similarity("random forests", "bagging predictors") < TITLE_SIMILARITY);
+    }
+
+    #[test]
+    fn duplicate_clusters() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@article{a, author = {Leo Breiman}, title = {Random Forests}, year = 2001}
+            @article{b, author = {Breiman, L.}, title = {{Random} forest}, year = 2001}
+            @article{c, author = {Leo Breiman}, title = {Random forests}, year = 2002}
+            @misc{d, title = {Something}, doi = {10.1023/a:1010933404324}}
+            @misc{e, title = {Something else}, doi = {https://doi.org/10.1023/A:1010933404324}}
+            @proceedings{conf, title = {Proceedings}, isbn = {978-0-465-07568-6}, year = 2020}
+            @inproceedings{p1, author = {Jane Doe}, title = {One}, crossref = {conf}}
+            @inproceedings{p2, author = {John Smith}, title = {Two}, crossref = {conf}}
+            @xdata{series, publisher = {Springer}}
+            @xdata{shared, isbn = {978-3-16-148410-0}}
+            @book{v1, author = {Jane Doe}, title = {Volume One}, xdata = {series, shared}}
+            @book{v2, author = {Jane Doe}, title = {Volume Two}, xdata = {series, shared}}",
+        )?;
+        // papers inherit the ISBN of their proceedings or xdata entries, which doesn't make
+        // them the same
+        assert_eq!(find_duplicates(bib.entries()), vec![vec![0, 1], vec![3, 4]]);
+        Ok(())
+    }
+}
@@ -1,2 +1,4 @@
 pub mod bibligraphy;
+pub mod crossref;
+pub mod duplicates;
 pub mod preprocessor;
@@ -1,10 +1,12 @@
 use std::path::PathBuf;
 
-use crate::styles::ReferenceStyle;
+use crate::parsing::citation::CitationSyntax;
+use crate::styles::{ReferenceStyle, SortOrder};
 use crate::Format;
 use anyhow::Result;
 
 use super::bibligraphy::Bibliography;
+use super::duplicates::DuplicatePolicy;
 use mdbook::book::Book;
 use mdbook::errors::Error;
 use mdbook::preprocess::{Preprocessor, PreprocessorContext};
@@ -45,20 +47,45 @@ impl Preprocessor for CitationPreprocessor {
                     .unwrap_or("ieee");
                 let style = ReferenceStyle::try_from(style_str)?;
 
-                let fail_fast = match cite_cfg
-                    .get("fail_fast")
+                let syntax_str = cite_cfg
+                    .get("syntax")
                     .and_then(|k| k.as_str())
-                    .unwrap_or("false")
-                {
-                    "false" => Ok(false),
-                    "true" => Ok(true),
-                    _ => Err(anyhow::Error::msg("could not parse fail_fast option")),
-                }?;
+                    .unwrap_or("latex");
+                let syntax = CitationSyntax::try_from(syntax_str)?;
+
+                let sort_str = cite_cfg
+                    .get("sort")
+                    .and_then(|k| k.as_str())
+                    .unwrap_or("style");
+                let order = SortOrder::try_from(sort_str)?;
+
+                let duplicates_str = cite_cfg
+                    .get("duplicates")
+                    .and_then(|k| k.as_str())
+                    .unwrap_or("first-wins");
+                let duplicates = DuplicatePolicy::try_from(duplicates_str)?;
 
-                let bibliography = Bibliography::from_files(bib_file_paths)?;
+                let fail_fast = bool_option(cite_cfg.get("fail_fast"), "fail_fast")?;
+                let lenient = bool_option(cite_cfg.get("lenient"), "lenient")?;
+                let markers = bool_option(cite_cfg.get("markers"), "markers")?;
+
+                let bibliography = if lenient {
+                    Bibliography::from_files_lenient(bib_file_paths, duplicates)?
+                } else {
+                    Bibliography::from_files(bib_file_paths, duplicates)?
+                };
                 book.for_each_mut(|item| {
-                    expandify_item(&bibliography, style, format, item, fail_fast)
-                        .expect("failed to expandify")
+                    expandify_item(
+                        &bibliography,
+                        style,
+                        format,
+                        syntax,
+                        item,
+                        markers,
+                        order,
+                        fail_fast,
+                    )
+                    .expect("failed to expandify")
                 });
                 Ok(book)
             } else {
@@ -70,30 +97,53 @@ impl Preprocessor for CitationPreprocessor {
     }
 }
 
+/// options can be either toml booleans or the strings "true" and "false". Defaults to false
+fn bool_option(value: Option<&Value>, name: &str) -> Result<bool> {
+    match value {
+        None => Ok(false),
+        Some(Value::Boolean(b)) => Ok(*b),
+        Some(Value::String(s)) if s == "false" => Ok(false),
+        Some(Value::String(s)) if s == "true" => Ok(true),
+        Some(_) => Err(anyhow::Error::msg(format!(
+            "could not parse {} option",
+            name
+        ))),
+    }
+}
+
 // TODO pick a better name
+#[allow(clippy::too_many_arguments)]
 fn expandify_item(
     bib: &Bibliography,
     style: ReferenceStyle,
     fmt: Format,
+    syntax: CitationSyntax,
     bi: &mut BookItem,
+    markers: bool,
+    order: SortOrder,
     fail_fast: bool,
 ) -> Result<()> {
     match bi {
         mdbook::BookItem::PartTitle(t) => {
-            let new = bib.expand_citations_in_string(t, style, fmt, fail_fast)?;
+            // titles can't hold a reference list, so only chapter contents get markers
+            let new =
+                bib.expand_citations_in_string(t, style, fmt, syntax, false, order, fail_fast)?;
             t.clear();
             t.push_str(&new);
             Ok(())
         }
         mdbook::BookItem::Chapter(c) => {
-            let new = bib.expand_citations_in_string(&c.content, style, fmt, fail_fast)?;
+            let new = bib.expand_citations_in_string(
+                &c.content, style, fmt, syntax, markers, order, fail_fast,
+            )?;
             c.content = new;
             let _ = c
                 .sub_items
                 .iter_mut()
-                .map(|si| expandify_item(bib, style, fmt, si, fail_fast))
+                .map(|si| expandify_item(bib, style, fmt, syntax, si, markers, order, fail_fast))
                 .collect::<Result<Vec<()>>>()?;
-            let new = bib.expand_citations_in_string(&c.name, style, fmt, fail_fast)?;
+            let new = bib
+                .expand_citations_in_string(&c.name, style, fmt, syntax, false, order, fail_fast)?;
             c.name = new;
             Ok(())
         }
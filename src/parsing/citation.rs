@@ -0,0 +1,550 @@
+use anyhow::anyhow;
+use nom::{
+    bytes::complete::{take_till, take_until, take_while1},
+    character::complete::{alpha1, char, multispace0, space0},
+    combinator::{opt, verify},
+    error::{Error, ErrorKind},
+    multi::{many0, separated_list1},
+    sequence::{delimited, preceded, tuple},
+    IResult,
+};
+use parse_hyperlinks::take_until_unbalanced;
+
+/// What a citation command puts in the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CitationMode {
+    /// the full reference, which is what `\cite` expands to
+    Full,
+    /// the citation in parentheses, e.g. "(Doe, 2020)" or "[1]"
+    Parenthetical,
+    /// the citation in parentheses without the authors, e.g. "(2020)" or "[1]"
+    SuppressAuthor,
+    /// the authors as part of the sentence, e.g. "Doe (2020)" or "Doe [1]"
+    Narrative,
+    /// only the authors, e.g. "Doe"
+    Author,
+    /// only the year, e.g. "2020"
+    Year,
+    /// nothing, the entry is only added to the bibliography
+    NoCite,
+}
+
+impl CitationMode {
+    /// the mode of a natbib or biblatex citation command, without its backslash
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "cite" => Some(Self::Full),
+            "parencite" | "Parencite" | "autocite" | "Autocite" | "citep" | "Citep" => {
+                Some(Self::Parenthetical)
+            }
+            "textcite" | "Textcite" | "citet" | "Citet" => Some(Self::Narrative),
+            "citeauthor" | "Citeauthor" => Some(Self::Author),
+            "citeyear" => Some(Self::Year),
+            "nocite" => Some(Self::NoCite),
+            _ => None,
+        }
+    }
+}
+
+/// A citation command in a text, like `\cite[see][p.~5]{a,b}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation<'a> {
+    pub mode: CitationMode,
+    pub keys: Vec<&'a str>,
+    /// the text before the references, like "see"
+    pub prenote: Option<&'a str>,
+    /// the text after the references, usually a locator like "p.~5"
+    pub postnote: Option<&'a str>,
+    /// the citation as it was written, so it can be left as is when it can't be expanded
+    pub raw: &'a str,
+}
+
+fn optional_argument(input: &str) -> IResult<&str, &str> {
+    preceded(
+        multispace0,
+        delimited(char('['), take_until_unbalanced('[', ']'), char(']')),
+    )(input)
+}
+
+fn citation_keys(input: &str) -> IResult<&str, Vec<&str>> {
+    delimited(
+        char('{'),
+        separated_list1(
+            char(','),
+            delimited(
+                multispace0,
+                take_while1(|c: char| c != ',' && c != '}' && !c.is_whitespace()),
+                multispace0,
+            ),
+        ),
+        char('}'),
+    )(input)
+}
+
+fn non_empty(note: Option<&str>) -> Option<&str> {
+    note.filter(|n| !n.trim().is_empty())
+}
+
+/// The syntax citations are written in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CitationSyntax {
+    /// `\cite{key}` and the other natbib and biblatex commands
+    #[default]
+    Latex,
+    /// `[@key]` and `@key` like in pandoc markdown
+    Pandoc,
+}
+
+impl TryFrom<&str> for CitationSyntax {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "latex" => Ok(CitationSyntax::Latex),
+            "pandoc" => Ok(CitationSyntax::Pandoc),
+            _ => Err(anyhow!("invalid citation syntax")),
+        }
+    }
+}
+
+impl CitationSyntax {
+    /// all citations in the input in this syntax, together with the text before them
+    pub fn all_citations(self, input: &str) -> IResult<&str, Vec<(&str, Citation<'_>)>> {
+        match self {
+            CitationSyntax::Latex => all_citations(input),
+            CitationSyntax::Pandoc => all_pandoc_citations(input),
+        }
+    }
+}
+
+/// the name of a citation command. `\parencite*` leaves out the authors, the starred
+/// versions of the natbib commands which list all authors are treated like the normal ones
+fn citation_command(input: &str) -> IResult<&str, CitationMode> {
+    let (tail, (command, starred)) = preceded(char('\\'), tuple((alpha1, opt(char('*')))))(input)?;
+    let mode = match (command, starred) {
+        ("parencite" | "Parencite", Some(_)) => Some(CitationMode::SuppressAuthor),
+        _ => CitationMode::from_command(command),
+    };
+    match mode {
+        Some(mode) => Ok((tail, mode)),
+        None => Err(nom::Err::Error(Error::new(input, ErrorKind::MapOpt))),
+    }
+}
+
+/// Parses a citation command. Like biblatex a single optional argument is the postnote,
+/// with two the first one is the prenote.
+pub fn citation(input: &str) -> IResult<&str, Citation<'_>> {
+    let (tail, (mode, first, second, keys)) = tuple((
+        citation_command,
+        opt(optional_argument),
+        opt(optional_argument),
+        preceded(multispace0, citation_keys),
+    ))(input)?;
+    let (prenote, postnote) = match (first, second) {
+        (Some(pre), Some(post)) => (Some(pre), Some(post)),
+        (post, _) => (None, post),
+    };
+    Ok((
+        tail,
+        Citation {
+            mode,
+            keys,
+            prenote: non_empty(prenote),
+            postnote: non_empty(postnote),
+            raw: &input[..input.len() - tail.len()],
+        },
+    ))
+}
+
+/// gives back tail, text consumed and the citation after it. Any other commands
+/// are skipped.
+pub fn next_citation(input: &str) -> IResult<&str, (&str, Citation<'_>)> {
+    let mut offset = 0;
+    loop {
+        let (rest, skipped) = take_until("\\")(&input[offset..])?;
+        offset += skipped.len();
+        if let Ok((tail, citation)) = citation(rest) {
+            return Ok((tail, (&input[..offset], citation)));
+        }
+        offset += 1;
+    }
+}
+
+pub fn all_citations(input: &str) -> IResult<&str, Vec<(&str, Citation<'_>)>> {
+    many0(next_citation)(input)
+}
+
+/// The commands where the reference list goes, `\printbibliography` with its optional
+/// argument and `\bibliography{file}`. Returns the command as it was written.
+pub fn bibliography_placeholder(input: &str) -> IResult<&str, &str> {
+    let (tail, command) = preceded(char('\\'), alpha1)(input)?;
+    let tail = match command {
+        "printbibliography" => opt(optional_argument)(tail)?.0,
+        "bibliography" => preceded(multispace0, citation_keys)(tail)?.0,
+        _ => return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag))),
+    };
+    Ok((tail, &input[..input.len() - tail.len()]))
+}
+
+/// like [`next_citation`] but for [`bibliography_placeholder`]s
+pub fn next_bibliography_placeholder(input: &str) -> IResult<&str, (&str, &str)> {
+    let mut offset = 0;
+    loop {
+        let (rest, skipped) = take_until("\\")(&input[offset..])?;
+        offset += skipped.len();
+        if let Ok((tail, placeholder)) = bibliography_placeholder(rest) {
+            return Ok((tail, (&input[..offset], placeholder)));
+        }
+        offset += 1;
+    }
+}
+
+pub fn all_bibliography_placeholders(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
+    many0(next_bibliography_placeholder)(input)
+}
+
+/// Pandoc citation keys start with a letter, digit or underscore and can contain
+/// punctuation, as long as it isn't at the end where it belongs to the sentence
+fn pandoc_key(input: &str) -> IResult<&str, &str> {
+    let is_key_char = |c: char| c.is_alphanumeric() || c == '_';
+    let (_, candidate) = verify(
+        take_while1(|c: char| is_key_char(c) || ":.#$%&-+?<>~/".contains(c)),
+        |k: &str| k.starts_with(is_key_char),
+    )(input)?;
+    let len = candidate.trim_end_matches(|c: char| !is_key_char(c)).len();
+    Ok((&input[len..], &input[..len]))
+}
+
+/// one of the `;` separated citations in brackets, like `see -@doe99, p. 33`
+struct PandocItem<'a> {
+    prefix: &'a str,
+    suppress_author: bool,
+    key: &'a str,
+    suffix: &'a str,
+}
+
+fn pandoc_item(input: &str) -> IResult<&str, PandocItem<'_>> {
+    let (rest, prefix) = take_till(|c| c == '@' || c == ';' || c == ']')(input)?;
+    let (prefix, suppress_author) = match prefix.strip_suffix('-') {
+        Some(p) => (p, true),
+        None => (prefix, false),
+    };
+    // an @ in the middle of a word is an email address
+    if prefix.ends_with(|c: char| !c.is_whitespace()) {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::Char)));
+    }
+    let (rest, key) = preceded(char('@'), pandoc_key)(rest)?;
+    let (rest, suffix) = take_till(|c| c == ';' || c == ']')(rest)?;
+    Ok((
+        rest,
+        PandocItem {
+            prefix: prefix.trim(),
+            suppress_author,
+            key,
+            suffix: suffix.trim().trim_start_matches(',').trim_start(),
+        },
+    ))
+}
+
+/// A parenthetical citation like `[see @a; -@b, p. 5]`. Like most styles only the prefix of
+/// the first and the suffix of the last item are kept.
+fn pandoc_citation(input: &str) -> IResult<&str, Citation<'_>> {
+    let (tail, items) = delimited(
+        char('['),
+        separated_list1(char(';'), pandoc_item),
+        char(']'),
+    )(input)?;
+    let mode = match items.iter().all(|i| i.suppress_author) {
+        true => CitationMode::SuppressAuthor,
+        false => CitationMode::Parenthetical,
+    };
+    Ok((
+        tail,
+        Citation {
+            mode,
+            keys: items.iter().map(|i| i.key).collect(),
+            prenote: non_empty(items.first().map(|i| i.prefix)),
+            postnote: non_empty(items.last().map(|i| i.suffix)),
+            raw: &input[..input.len() - tail.len()],
+        },
+    ))
+}
+
+/// A narrative citation like `@doe99`, optionally followed by a locator like `@doe99 [p. 33]`
+fn pandoc_narrative_citation(input: &str) -> IResult<&str, Citation<'_>> {
+    let (tail, (key, postnote)) = tuple((
+        preceded(char('@'), pandoc_key),
+        opt(preceded(
+            space0,
+            delimited(
+                char('['),
+                take_till(|c| c == '@' || c == '[' || c == ']'),
+                char(']'),
+            ),
+        )),
+    ))(input)?;
+    Ok((
+        tail,
+        Citation {
+            mode: CitationMode::Narrative,
+            keys: vec![key],
+            prenote: None,
+            postnote: non_empty(postnote.map(str::trim)),
+            raw: &input[..input.len() - tail.len()],
+        },
+    ))
+}
+
+/// The length of the Markdown code at the start of `input`, which is a fenced code block
+/// if it starts a line and an inline code span otherwise. Code is left as it is, so an `@`
+/// in it is not a citation.
+fn markdown_code_len(input: &str, at_line_start: bool) -> Option<usize> {
+    let fence = input.chars().next()?;
+    let run = input.len() - input.trim_start_matches(fence).len();
+    if at_line_start && run >= 3 {
+        // the block ends at a fence at least as long, or at the end of the text
+        let mut pos = input.find('\n').map_or(input.len(), |i| i + 1);
+        while pos < input.len() {
+            let end = input[pos..].find('\n').map_or(input.len(), |i| pos + i + 1);
+            let line = input[pos..end].trim_start_matches(' ');
+            let closing = line.len() - line.trim_start_matches(fence).len();
+            if closing >= run && line[closing..].trim().is_empty() {
+                return Some(end);
+            }
+            pos = end;
+        }
+        return Some(input.len());
+    }
+    if fence != '`' {
+        return None;
+    }
+    // an inline code span ends at the next run of as many backticks
+    let mut pos = run;
+    while let Some(i) = input[pos..].find('`') {
+        let start = pos + i;
+        let len = input[start..].len() - input[start..].trim_start_matches('`').len();
+        if len == run {
+            return Some(start + len);
+        }
+        pos = start + len;
+    }
+    // backticks that are never closed are just backticks
+    Some(run)
+}
+
+/// like [`next_citation`] but for pandoc citations, which are not looked for in code
+pub fn next_pandoc_citation(input: &str) -> IResult<&str, (&str, Citation<'_>)> {
+    let mut offset = 0;
+    loop {
+        let (rest, skipped) =
+            take_till(|c| c == '[' || c == '@' || c == '`' || c == '~')(&input[offset..])?;
+        offset += skipped.len();
+        if rest.is_empty() {
+            return Err(nom::Err::Error(Error::new(rest, ErrorKind::TakeTill1)));
+        }
+        if rest.starts_with(['`', '~']) {
+            let line = input[..offset].trim_end_matches(' ');
+            let at_line_start = line.is_empty() || line.ends_with('\n');
+            offset += markdown_code_len(rest, at_line_start).unwrap_or(1);
+            continue;
+        }
+        let after_word = input[..offset].ends_with(|c: char| c.is_alphanumeric());
+        let parsed = match rest.starts_with('[') {
+            true => pandoc_citation(rest),
+            false if !after_word => pandoc_narrative_citation(rest),
+            false => Err(nom::Err::Error(Error::new(rest, ErrorKind::Char))),
+        };
+        if let Ok((tail, citation)) = parsed {
+            return Ok((tail, (&input[..offset], citation)));
+        }
+        offset += 1;
+    }
+}
+
+pub fn all_pandoc_citations(input: &str) -> IResult<&str, Vec<(&str, Citation<'_>)>> {
+    many0(next_pandoc_citation)(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn text_citation_simple() -> Result<()> {
+        let input = "         asd;lkfjwliefjxcajvnasledifm; sei help I'm stuck in a sub factory! asdoifmwae;va \\cite{book}.lkfjwliefjxcajvnasledifm";
+        let (tail, (unmodified, citation)) = next_citation(input)?;
+
+        assert_eq!(tail, ".lkfjwliefjxcajvnasledifm");
+        assert_eq!(unmodified,"         asd;lkfjwliefjxcajvnasledifm; sei help I'm stuck in a sub factory! asdoifmwae;va ");
+        assert_eq!(citation.keys, vec!["book"]);
+        assert_eq!(citation.raw, "\\cite{book}");
+        Ok(())
+    }
+
+    #[test]
+    fn book_example() -> Result<()> {
+        let input =
+            "#cite \r\n\r\n[^cms]: \\cite{cms}\r\n[^ieee]: \\cite{ieee}\r\n[^doi]: \\cite{doi}\r\n";
+        let (tail, (_unmodified, citation)) = next_citation(input)?;
+
+        assert_eq!(tail, "\r\n[^ieee]: \\cite{ieee}\r\n[^doi]: \\cite{doi}\r\n");
+        assert_eq!(citation.keys, vec!["cms"]);
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_keys_and_notes() -> Result<()> {
+        for (input, keys, prenote, postnote) in [
+            ("\\cite{a,b}", vec!["a", "b"], None, None),
+            ("\\cite{a, b ,c}", vec!["a", "b", "c"], None, None),
+            ("\\cite[p.~5]{a}", vec!["a"], None, Some("p.~5")),
+            (
+                "\\cite[see][p.~5]{a,b}",
+                vec!["a", "b"],
+                Some("see"),
+                Some("p.~5"),
+            ),
+            ("\\cite[see][]{a}", vec!["a"], Some("see"), None),
+            ("\\cite[][fig. [2]]{a}", vec!["a"], None, Some("fig. [2]")),
+        ] {
+            let (tail, citation) = citation(input)?;
+            assert_eq!(tail, "", "{}", input);
+            assert_eq!(citation.keys, keys, "{}", input);
+            assert_eq!(citation.prenote, prenote, "{}", input);
+            assert_eq!(citation.postnote, postnote, "{}", input);
+            assert_eq!(citation.raw, input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn other_commands_are_skipped() -> Result<()> {
+        let (tail, citations) = all_citations("\\citefoo{x} and \\cite{a}, \\cite{b")?;
+        assert_eq!(citations.len(), 1);
+        assert_eq!(citations[0].0, "\\citefoo{x} and ");
+        assert_eq!(citations[0].1.keys, vec!["a"]);
+        assert_eq!(tail, ", \\cite{b");
+        Ok(())
+    }
+
+    #[test]
+    fn citation_commands() -> Result<()> {
+        for (input, mode) in [
+            ("\\cite{a}", CitationMode::Full),
+            ("\\parencite{a}", CitationMode::Parenthetical),
+            ("\\autocite[p.~5]{a}", CitationMode::Parenthetical),
+            ("\\citep*{a}", CitationMode::Parenthetical),
+            ("\\textcite{a}", CitationMode::Narrative),
+            ("\\Citet{a,b}", CitationMode::Narrative),
+            ("\\citeauthor{a}", CitationMode::Author),
+            ("\\citeyear{a}", CitationMode::Year),
+            ("\\nocite{*}", CitationMode::NoCite),
+        ] {
+            let (tail, citation) = citation(input)?;
+            assert_eq!(tail, "", "{}", input);
+            assert_eq!(citation.mode, mode, "{}", input);
+        }
+        let (tail, citations) = all_citations("\\emph{x} \\citet{a} and \\citeyearpar{b}")?;
+        assert_eq!(citations.len(), 1);
+        assert_eq!(citations[0].0, "\\emph{x} ");
+        assert_eq!(tail, " and \\citeyearpar{b}");
+        Ok(())
+    }
+
+    #[test]
+    fn pandoc_citations() -> Result<()> {
+        for (input, mode, keys, prenote, postnote) in [
+            ("[@a]", CitationMode::Parenthetical, vec!["a"], None, None),
+            (
+                "[see @a; @b, p. 5]",
+                CitationMode::Parenthetical,
+                vec!["a", "b"],
+                Some("see"),
+                Some("p. 5"),
+            ),
+            ("[-@a]", CitationMode::SuppressAuthor, vec!["a"], None, None),
+            ("@a", CitationMode::Narrative, vec!["a"], None, None),
+            (
+                "@a [p. 5]",
+                CitationMode::Narrative,
+                vec!["a"],
+                None,
+                Some("p. 5"),
+            ),
+            (
+                "@doe:99.",
+                CitationMode::Narrative,
+                vec!["doe:99"],
+                None,
+                None,
+            ),
+        ] {
+            let (_, citations) = all_pandoc_citations(input)?;
+            assert_eq!(citations.len(), 1, "{}", input);
+            let citation = &citations[0].1;
+            assert_eq!(citation.mode, mode, "{}", input);
+            assert_eq!(citation.keys, keys, "{}", input);
+            assert_eq!(citation.prenote, prenote, "{}", input);
+            assert_eq!(citation.postnote, postnote, "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pandoc_skips_links_footnotes_and_emails() -> Result<()> {
+        let input = "[a link](https://x.org), a note[^n] and mail@example.com but @a.";
+        let (tail, citations) = all_pandoc_citations(input)?;
+        assert_eq!(citations.len(), 1);
+        assert_eq!(
+            citations[0].0,
+            "[a link](https://x.org), a note[^n] and mail@example.com but "
+        );
+        assert_eq!(citations[0].1.raw, "@a");
+        assert_eq!(tail, ".");
+        Ok(())
+    }
+
+    #[test]
+    fn pandoc_skips_inline_code() -> Result<()> {
+        let input = "Decorate with `@decorator` or ``[@a]`` but cite @b.";
+        let (tail, citations) = all_pandoc_citations(input)?;
+        assert_eq!(citations.len(), 1);
+        assert_eq!(
+            citations[0].0,
+            "Decorate with `@decorator` or ``[@a]`` but cite "
+        );
+        assert_eq!(citations[0].1.raw, "@b");
+        assert_eq!(tail, ".");
+        Ok(())
+    }
+
+    #[test]
+    fn pandoc_skips_fenced_code() -> Result<()> {
+        let input = "See @a.\n\n```python\n@decorator\ndef f(): ...\n```\n\n~~~\n[@b]\n~~~\nand @c";
+        let (tail, citations) = all_pandoc_citations(input)?;
+        let keys: Vec<&str> = citations.iter().flat_map(|c| c.1.keys.clone()).collect();
+        assert_eq!(keys, vec!["a", "c"]);
+        assert_eq!(
+            citations[1].0,
+            ".\n\n```python\n@decorator\ndef f(): ...\n```\n\n~~~\n[@b]\n~~~\nand "
+        );
+        assert_eq!(tail, "");
+        Ok(())
+    }
+
+    #[test]
+    fn bibliography_placeholders() -> Result<()> {
+        let input = "\\bibliographystyle{plain}\n\\bibliography{refs, more}\n\\printbibliography[heading=none]\\printbibliographyheading";
+        let (tail, placeholders) = all_bibliography_placeholders(input)?;
+        assert_eq!(
+            placeholders,
+            vec![
+                ("\\bibliographystyle{plain}\n", "\\bibliography{refs, more}"),
+                ("\n", "\\printbibliography[heading=none]"),
+            ]
+        );
+        assert_eq!(tail, "\\printbibliographyheading");
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while_m_n},
+    character::complete::{char, one_of, space0},
+    combinator::{all_consuming, map, map_opt, map_res, opt, recognize, verify},
+    sequence::{pair, preceded, terminated, tuple},
+    IResult,
+};
+
+/// A month of the year, however it was written in the bib file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Month {
+    January,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+impl Month {
+    pub const ALL: [Month; 12] = [
+        Month::January,
+        Month::February,
+        Month::March,
+        Month::April,
+        Month::May,
+        Month::June,
+        Month::July,
+        Month::August,
+        Month::September,
+        Month::October,
+        Month::November,
+        Month::December,
+    ];
+
+    /// The month with the given number, starting at 1 for January
+    pub fn from_number(number: u32) -> Option<Self> {
+        Self::ALL.get(number.checked_sub(1)? as usize).copied()
+    }
+
+    /// Parse a month written as a number or (abbreviated) english name, e.g. `7`, `07`,
+    /// `jul`, `Jul.` or `July`
+    pub fn parse(input: &str) -> Option<Self> {
+        let month = input.trim().trim_end_matches('.').to_lowercase();
+        if let Ok(m) = month.parse::<u32>() {
+            return Self::from_number(m);
+        }
+        if month.len() < 3 {
+            return None;
+        }
+        Self::ALL
+            .into_iter()
+            .find(|m| m.name().to_lowercase().starts_with(&month))
+    }
+
+    pub fn number(&self) -> u32 {
+        *self as u32 + 1
+    }
+
+    /// The full name, e.g. "January"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Month::January => "January",
+            Month::February => "February",
+            Month::March => "March",
+            Month::April => "April",
+            Month::May => "May",
+            Month::June => "June",
+            Month::July => "July",
+            Month::August => "August",
+            Month::September => "September",
+            Month::October => "October",
+            Month::November => "November",
+            Month::December => "December",
+        }
+    }
+
+    /// The abbreviated name, e.g. "Jan.". Short names like "May" aren't abbreviated.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Month::January => "Jan.",
+            Month::February => "Feb.",
+            Month::March => "Mar.",
+            Month::April => "Apr.",
+            Month::May => "May",
+            Month::June => "Jun.",
+            Month::July => "Jul.",
+            Month::August => "Aug.",
+            Month::September => "Sep.",
+            Month::October => "Oct.",
+            Month::November => "Nov.",
+            Month::December => "Dec.",
+        }
+    }
+
+    /// The name of the macro bibtex predefines for this month, e.g. `jan`
+    pub fn macro_name(&self) -> String {
+        self.name()[..3].to_lowercase()
+    }
+}
+
+/// A (partial) calendar date, e.g. `2020`, `2020-05` or `2020-05-12`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: Option<Month>,
+    pub day: Option<u32>,
+}
+
+/// The date of an entry, from the biblatex `date` field or the bibtex `year` and `month` fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryDate {
+    /// a single date, `circa` for approximate dates like `1850~` or `ca. 1850`
+    Single { date: Date, circa: bool },
+    /// a range like `2019/2021`, without an end for open ranges like `2019/` or `2019/..`
+    Range {
+        start: Date,
+        end: Option<Date>,
+        circa: bool,
+    },
+    /// anything that isn't a date, like `forthcoming` or `2021a`, as it was written
+    Literal(String),
+}
+
+impl Date {
+    fn is_valid(&self) -> bool {
+        match (self.month, self.day) {
+            (None, None) | (Some(_), None) => true,
+            (Some(m), Some(d)) => NaiveDate::from_ymd_opt(self.year, m.number(), d).is_some(),
+            (None, Some(_)) => false,
+        }
+    }
+}
+
+impl EntryDate {
+    /// Parse a date as written in a `date`, `year` or `urldate` field. Anything that
+    /// can't be parsed is kept as a [`EntryDate::Literal`].
+    pub fn parse(input: &str) -> Self {
+        let trimmed = input.trim();
+        match all_consuming(alt((range, single)))(trimmed) {
+            Ok((_, date)) => date,
+            Err(_) => EntryDate::Literal(trimmed.to_string()),
+        }
+    }
+
+    /// The date of an entry, preferring the `date` field over `year` and `month`
+    pub fn from_fields(fields: &BTreeMap<String, String>) -> Option<Self> {
+        if let Some(d) = fields.get("date") {
+            return Some(Self::parse(d));
+        }
+        let parsed = Self::parse(fields.get("year")?);
+        let month = match fields.get("month") {
+            Some(m) => m,
+            None => return Some(parsed),
+        };
+        Some(match (parsed, Month::parse(month)) {
+            (
+                EntryDate::Single {
+                    date:
+                        Date {
+                            year,
+                            month: None,
+                            day: None,
+                        },
+                    circa,
+                },
+                Some(m),
+            ) => EntryDate::Single {
+                date: Date {
+                    year,
+                    month: Some(m),
+                    day: None,
+                },
+                circa,
+            },
+            // a month doesn't mean anything without a year, e.g. for `forthcoming` works
+            (EntryDate::Literal(y), _) if !y.contains(|c: char| c.is_ascii_digit()) => {
+                EntryDate::Literal(y)
+            }
+            // we don't know where to put the month, so keep it as written
+            (EntryDate::Literal(y), Some(m)) => EntryDate::Literal(format!("{} {}", m.name(), y)),
+            (EntryDate::Literal(y), None) => {
+                EntryDate::Literal(format!("{} {}", capitalise(month), y))
+            }
+            (EntryDate::Single { date, circa }, None) if date.month.is_none() => {
+                EntryDate::Literal(format!(
+                    "{} {}{}",
+                    capitalise(month),
+                    if circa { "ca. " } else { "" },
+                    date.year
+                ))
+            }
+            (d, _) => d,
+        })
+    }
+
+    /// The date the `url` of an entry was last accessed
+    pub fn urldate(fields: &BTreeMap<String, String>) -> Option<Self> {
+        fields.get("urldate").map(|d| Self::parse(d))
+    }
+
+    /// The same date, but without the month and day
+    pub fn year_only(&self) -> Self {
+        let strip = |d: &Date| Date {
+            year: d.year,
+            month: None,
+            day: None,
+        };
+        match self {
+            EntryDate::Single { date, circa } => EntryDate::Single {
+                date: strip(date),
+                circa: *circa,
+            },
+            EntryDate::Range { start, end, circa } => {
+                let (start, end) = (strip(start), end.as_ref().map(strip));
+                if end == Some(start) {
+                    EntryDate::Single {
+                        date: start,
+                        circa: *circa,
+                    }
+                } else {
+                    EntryDate::Range {
+                        start,
+                        end,
+                        circa: *circa,
+                    }
+                }
+            }
+            EntryDate::Literal(l) => EntryDate::Literal(l.clone()),
+        }
+    }
+
+    /// The (first) year of the date, if it has one. For literals like `2021a` this
+    /// is the number they start with.
+    pub fn year(&self) -> Option<i32> {
+        match self {
+            EntryDate::Single { date, .. } => Some(date.year),
+            EntryDate::Range { start, .. } => Some(start.year),
+            EntryDate::Literal(l) => {
+                let digits = l.len() - l.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                l[..digits].parse().ok()
+            }
+        }
+    }
+}
+
+fn capitalise(input: &str) -> String {
+    let mut chars = input.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn digits<'a>(min: usize, max: usize) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    take_while_m_n(min, max, |c: char| c.is_ascii_digit())
+}
+
+fn date(input: &str) -> IResult<&str, Date> {
+    verify(
+        map(
+            tuple((
+                map_res(recognize(pair(opt(char('-')), digits(1, 4))), str::parse),
+                opt(pair(
+                    map_opt(preceded(char('-'), digits(2, 2)), |m: &str| {
+                        Month::from_number(m.parse().ok()?)
+                    }),
+                    opt(map_res(preceded(char('-'), digits(2, 2)), str::parse)),
+                )),
+            )),
+            |(year, rest)| Date {
+                year,
+                month: rest.map(|(m, _)| m),
+                day: rest.and_then(|(_, d)| d),
+            },
+        ),
+        Date::is_valid,
+    )(input)
+}
+
+/// `~` marks approximate dates, `?` uncertain ones and `%` both
+fn qualifier(input: &str) -> IResult<&str, bool> {
+    map(opt(one_of("~?%")), |q| q.is_some())(input)
+}
+
+fn circa_prefix(input: &str) -> IResult<&str, bool> {
+    map(
+        opt(terminated(
+            alt((tag_no_case("circa"), tag_no_case("ca."), tag_no_case("c."))),
+            space0,
+        )),
+        |p| p.is_some(),
+    )(input)
+}
+
+fn single(input: &str) -> IResult<&str, EntryDate> {
+    map(
+        tuple((circa_prefix, date, qualifier)),
+        |(prefix, date, suffix)| EntryDate::Single {
+            date,
+            circa: prefix || suffix,
+        },
+    )(input)
+}
+
+fn range(input: &str) -> IResult<&str, EntryDate> {
+    map(
+        tuple((
+            circa_prefix,
+            date,
+            qualifier,
+            alt((tag("/"), tag("--"), tag("–"))),
+            alt((
+                map(pair(date, qualifier), Some),
+                map(opt(tag("..")), |_| None),
+            )),
+        )),
+        |(prefix, start, start_circa, _, end)| EntryDate::Range {
+            start,
+            end: end.map(|(d, _)| d),
+            circa: prefix || start_circa || end.is_some_and(|(_, c)| c),
+        },
+    )(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ymd(year: i32, month: Option<u32>, day: Option<u32>) -> Date {
+        Date {
+            year,
+            month: month.and_then(Month::from_number),
+            day,
+        }
+    }
+
+    #[test]
+    fn parse_single_dates() {
+        assert_eq!(
+            EntryDate::parse("2020-05-12"),
+            EntryDate::Single {
+                date: ymd(2020, Some(5), Some(12)),
+                circa: false
+            }
+        );
+        assert_eq!(
+            EntryDate::parse(" 2020-05 "),
+            EntryDate::Single {
+                date: ymd(2020, Some(5), None),
+                circa: false
+            }
+        );
+        assert_eq!(
+            EntryDate::parse("1850~"),
+            EntryDate::Single {
+                date: ymd(1850, None, None),
+                circa: true
+            }
+        );
+        assert_eq!(
+            EntryDate::parse("ca. 1850"),
+            EntryDate::Single {
+                date: ymd(1850, None, None),
+                circa: true
+            }
+        );
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(
+            EntryDate::parse("2019/2021"),
+            EntryDate::Range {
+                start: ymd(2019, None, None),
+                end: Some(ymd(2021, None, None)),
+                circa: false
+            }
+        );
+        assert_eq!(
+            EntryDate::parse("2019-01/2019-03"),
+            EntryDate::Range {
+                start: ymd(2019, Some(1), None),
+                end: Some(ymd(2019, Some(3), None)),
+                circa: false
+            }
+        );
+        for open in ["2019/", "2019/.."] {
+            assert_eq!(
+                EntryDate::parse(open),
+                EntryDate::Range {
+                    start: ymd(2019, None, None),
+                    end: None,
+                    circa: false
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn non_numeric_dates_are_literals() {
+        for literal in ["2021a", "forthcoming", "2020-13", "2021-02-30", "in press"] {
+            assert_eq!(
+                EntryDate::parse(literal),
+                EntryDate::Literal(literal.to_string())
+            );
+        }
+        assert_eq!(EntryDate::parse("2021a").year(), Some(2021));
+        assert_eq!(EntryDate::parse("forthcoming").year(), None);
+    }
+
+    #[test]
+    fn date_from_fields() {
+        let mut fields = BTreeMap::new();
+        assert_eq!(EntryDate::from_fields(&fields), None);
+        fields.insert("year".to_string(), "2015".to_string());
+        fields.insert("month".to_string(), "jul".to_string());
+        assert_eq!(
+            EntryDate::from_fields(&fields),
+            Some(EntryDate::Single {
+                date: ymd(2015, Some(7), None),
+                circa: false
+            })
+        );
+        fields.insert("month".to_string(), "summer".to_string());
+        assert_eq!(
+            EntryDate::from_fields(&fields),
+            Some(EntryDate::Literal("Summer 2015".to_string()))
+        );
+        fields.insert("year".to_string(), "forthcoming".to_string());
+        fields.insert("month".to_string(), "jan".to_string());
+        assert_eq!(
+            EntryDate::from_fields(&fields),
+            Some(EntryDate::Literal("forthcoming".to_string()))
+        );
+        fields.insert("date".to_string(), "2016-08-01".to_string());
+        assert_eq!(
+            EntryDate::from_fields(&fields),
+            Some(EntryDate::Single {
+                date: ymd(2016, Some(8), Some(1)),
+                circa: false
+            })
+        );
+    }
+
+    #[test]
+    fn months() {
+        for written in ["jan", "Jan.", "January", "JANUARY", "1", "01"] {
+            assert_eq!(Month::parse(written), Some(Month::January));
+        }
+        assert_eq!(Month::parse("08"), Some(Month::August));
+        assert_eq!(Month::parse("sep"), Some(Month::September));
+        assert_eq!(Month::parse("Sept."), Some(Month::September));
+        assert_eq!(Month::parse("13"), None);
+        assert_eq!(Month::parse("0"), None);
+        assert_eq!(Month::parse("ma"), None);
+        assert_eq!(Month::May.abbreviation(), "May");
+        assert_eq!(Month::December.macro_name(), "dec");
+    }
+}
@@ -4,20 +4,105 @@ use std::{collections::BTreeMap, fmt::Debug};
 use anyhow::Result;
 // lint allows are just while developing, will be removed soon
 use nom::{
-    branch::{alt, permutation},
-    bytes::complete::{tag, tag_no_case, take_till, take_till1, take_until},
-    character::{
-        complete::{char, line_ending, multispace0},
-        is_space,
-    },
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_till1, take_while1},
+    character::complete::{char, multispace0},
+    combinator::{all_consuming, map, opt, verify},
+    error::{Error, ErrorKind},
     multi::{many0, separated_list1},
-    sequence::{delimited, preceded, separated_pair, terminated},
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
     IResult,
 };
 use parse_hyperlinks::take_until_unbalanced;
 
-use super::names::{and_seperated_names, OwnedFullName};
-pub type EntrySubComponents<'a> = (EntryType, &'a str, Vec<(&'a str, &'a str)>);
+use super::{
+    date::Month,
+    error::{offset_in, BibParseError},
+    latex::decode_latex,
+    names::{and_seperated_names, OwnedFullName},
+};
+pub type EntrySubComponents<'a> = (EntryType, &'a str, Vec<(&'a str, FieldValue<'a>)>);
+
+/// string macros defined with `@string`, keyed by their lowercased name
+pub type StringMacros = BTreeMap<String, String>;
+
+/// lists of names other than the authors, keyed by the name of their field, e.g. `editor`
+pub type NameLists = BTreeMap<String, Vec<OwnedFullName>>;
+
+/// Fields that hold a list of names seperated by `and`, like `author` does
+pub const NAME_LIST_FIELDS: &[&str] = &[
+    "editor",
+    "editora",
+    "editorb",
+    "editorc",
+    "translator",
+    "bookauthor",
+    "annotator",
+    "commentator",
+    "introduction",
+    "foreword",
+    "afterword",
+    "holder",
+];
+
+/// The macros bibtex predefines for the months, e.g. `jan` for "January"
+pub fn predefined_macros() -> StringMacros {
+    Month::ALL
+        .iter()
+        .map(|m| (m.macro_name(), m.name().to_string()))
+        .collect()
+}
+
+/// Fields that are taken as is, without decoding any LaTeX in them. Dates use `~` for
+/// approximate dates, so they shouldn't be decoded either.
+const VERBATIM_FIELDS: &[&str] = &[
+    "url",
+    "doi",
+    "eprint",
+    "file",
+    "pdf",
+    "urlraw",
+    "date",
+    "urldate",
+    "eventdate",
+    "origdate",
+];
+
+/// Field values can be concatenated from several parts using `#`
+pub type FieldValue<'a> = Vec<ValuePart<'a>>;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ValuePart<'a> {
+    /// a brace or quote delimited string, or a bare number
+    Literal(&'a str),
+    /// a bare identifier refering to a `@string` macro
+    Macro(&'a str),
+}
+
+/// The top level items that can appear in a bib file
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BibItem<'a> {
+    Entry(EntrySubComponents<'a>),
+    StringDefinition(&'a str, FieldValue<'a>),
+    Preamble(FieldValue<'a>),
+    Comment(&'a str),
+}
+
+/// Expand a (possibly concatenated) field value into a single string.
+/// Macros that are not defined are kept verbatim, mirroring the lenient
+/// behaviour bibtex has for them.
+pub fn expand_field_value(value: &[ValuePart], macros: &StringMacros) -> String {
+    value.iter().fold(String::new(), |mut acc, part| {
+        match part {
+            ValuePart::Literal(l) => acc.push_str(l),
+            ValuePart::Macro(m) => match macros.get(&m.to_lowercase()) {
+                Some(expanded) => acc.push_str(expanded),
+                None => acc.push_str(m),
+            },
+        };
+        acc
+    })
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum EntryType {
@@ -35,6 +120,20 @@ pub enum EntryType {
     Proceedings,
     Techreport,
     Unpublished,
+    // biblatex types
+    Online,
+    Software,
+    Dataset,
+    Report,
+    /// the kind of thesis is determined by the `type` field
+    Thesis,
+    Patent,
+    Collection,
+    Mvbook,
+    Periodical,
+    Standard,
+    /// any type we don't know, these are formatted like misc
+    Other(String),
 }
 
 impl fmt::Display for EntryType {
@@ -54,52 +153,54 @@ impl fmt::Display for EntryType {
             EntryType::Proceedings => write!(f, "Proceedings"),
             EntryType::Techreport => write!(f, "Techreport"),
             EntryType::Unpublished => write!(f, "Unpublished"),
+            EntryType::Online => write!(f, "Online"),
+            EntryType::Software => write!(f, "Software"),
+            EntryType::Dataset => write!(f, "Dataset"),
+            EntryType::Report => write!(f, "Report"),
+            EntryType::Thesis => write!(f, "Thesis"),
+            EntryType::Patent => write!(f, "Patent"),
+            EntryType::Collection => write!(f, "Collection"),
+            EntryType::Mvbook => write!(f, "Mvbook"),
+            EntryType::Periodical => write!(f, "Periodical"),
+            EntryType::Standard => write!(f, "Standard"),
+            EntryType::Other(kind) => write!(f, "{}", kind),
         }
     }
 }
 
-impl TryFrom<&str> for EntryType {
-    type Error = &'static str;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+/// expects a lowercase entry type. Types we don't know become [`EntryType::Other`]
+impl From<&str> for EntryType {
+    fn from(value: &str) -> Self {
         match value {
-            "article" => Ok(EntryType::Article),
-            "booklet" => Ok(EntryType::Booklet),
-            "conference" => Ok(EntryType::Conference),
-            "inbook" => Ok(EntryType::Inbook),
-            "book" => Ok(EntryType::Book),
-            "incollection" => Ok(EntryType::Incollection),
-            "inproceedings" => Ok(EntryType::Inproceedings),
-            "manual" => Ok(EntryType::Manual),
-            "mastersthesis" => Ok(EntryType::Mastersthesis),
-            "misc" => Ok(EntryType::Misc),
-            "phdthesis" => Ok(EntryType::Phdthesis),
-            "proceedings" => Ok(EntryType::Proceedings),
-            "techreport" => Ok(EntryType::Techreport),
-            "unpublished" => Ok(EntryType::Unpublished),
-            _ => Err("unknown kind"),
+            "article" => EntryType::Article,
+            "booklet" => EntryType::Booklet,
+            "conference" => EntryType::Conference,
+            "inbook" => EntryType::Inbook,
+            "book" => EntryType::Book,
+            "incollection" => EntryType::Incollection,
+            "inproceedings" => EntryType::Inproceedings,
+            "manual" => EntryType::Manual,
+            "mastersthesis" => EntryType::Mastersthesis,
+            "misc" => EntryType::Misc,
+            "phdthesis" => EntryType::Phdthesis,
+            "proceedings" => EntryType::Proceedings,
+            "techreport" => EntryType::Techreport,
+            "unpublished" => EntryType::Unpublished,
+            "online" | "electronic" | "www" => EntryType::Online,
+            "software" => EntryType::Software,
+            "dataset" => EntryType::Dataset,
+            "report" => EntryType::Report,
+            "thesis" => EntryType::Thesis,
+            "patent" => EntryType::Patent,
+            "collection" => EntryType::Collection,
+            "mvbook" => EntryType::Mvbook,
+            "periodical" => EntryType::Periodical,
+            "standard" => EntryType::Standard,
+            other => EntryType::Other(other.to_string()),
         }
     }
 }
 
-/// Searches the input for a cite tag
-pub fn citation(input: &str) -> IResult<&str, &str> {
-    preceded(
-        tag("\\cite"),
-        delimited(char('{'), take_until("}"), char('}')),
-    )(input)
-}
-/// gives back tail, text consumed
-pub fn next_citation(input: &str) -> IResult<&str, (&str, &str)> {
-    let (tail, unmodified) = take_until("\\cite")(input)?;
-    let (tail, citation_key) = citation(tail)?;
-
-    Ok((tail, (unmodified, citation_key)))
-}
-pub fn all_citations(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
-    many0(next_citation)(input)
-}
-
 #[derive(PartialEq, Eq, Clone)]
 pub struct BibEntry {
     pub kind: EntryType,
@@ -107,6 +208,8 @@ pub struct BibEntry {
     // authors have a special data set and are also included in μ-almost all entries
     // so it get's special treatment
     pub authors: Vec<OwnedFullName>,
+    /// the other name lists like `editor` and `translator`, see [`NAME_LIST_FIELDS`]
+    pub names: NameLists,
     pub fields: BTreeMap<String, String>,
 }
 
@@ -117,9 +220,10 @@ impl BibEntry {
         EntryType,
         String,
         Vec<OwnedFullName>,
+        NameLists,
         BTreeMap<String, String>,
     ) {
-        (self.kind, self.key, self.authors, self.fields)
+        (self.kind, self.key, self.authors, self.names, self.fields)
     }
 }
 
@@ -130,6 +234,12 @@ impl Debug for BibEntry {
         for auth in self.authors.iter() {
             writeln!(f, "    - {:?}", auth)?;
         }
+        for (k, names) in self.names.iter() {
+            writeln!(f, "  - {}:", k)?;
+            for name in names {
+                writeln!(f, "    - {:?}", name)?;
+            }
+        }
         for (k, v) in self.fields.iter() {
             writeln!(f, "  - {} = {}", k, v)?;
         }
@@ -138,119 +248,181 @@ impl Debug for BibEntry {
     }
 }
 
-impl<'a> From<(EntryType, &'a str, Vec<(&'a str, &'a str)>)> for BibEntry {
-    fn from(value: (EntryType, &'a str, Vec<(&'a str, &'a str)>)) -> Self {
+impl<'a> TryFrom<EntrySubComponents<'a>> for BibEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(value: EntrySubComponents<'a>) -> Result<Self, Self::Error> {
+        Self::from_components(value, &predefined_macros())
+    }
+}
+
+impl BibEntry {
+    /// construct an entry from it's parsed components, expanding any macros
+    /// using the provided definitions
+    pub fn from_components(value: EntrySubComponents, macros: &StringMacros) -> Result<Self> {
         let mut fields = BTreeMap::new();
 
         for (k, v) in value.2 {
-            // authors get special treatment
-            fields.insert(String::from(k), String::from(v));
+            // field names are case insensitive in bibtex
+            let name = k.to_lowercase();
+            let mut value = expand_field_value(&v, macros);
+            // names are decoded per part after they are split
+            if name != "author"
+                && !NAME_LIST_FIELDS.contains(&name.as_str())
+                && !VERBATIM_FIELDS.contains(&name.as_str())
+            {
+                value = decode_latex(&value);
+            }
+            fields.insert(name, value);
         }
 
-        let authors: Vec<OwnedFullName> = match fields.remove_entry("author") {
-            Some((_k, v)) => {
-                let (_tail, auth) = and_seperated_names(&v).unwrap();
-                auth.into_iter().map(|n| n.into()).collect()
-            }
+        let langid = fields
+            .get("langid")
+            .or_else(|| fields.get("language"))
+            .cloned();
+        let authors = match fields.remove("author") {
+            Some(v) => name_list("author", &v, &langid)?,
             None => vec![],
         };
+        let mut names = NameLists::new();
+        for field in NAME_LIST_FIELDS {
+            if let Some(v) = fields.remove(*field) {
+                names.insert(field.to_string(), name_list(field, &v, &langid)?);
+            }
+        }
 
-        Self {
+        Ok(Self {
             kind: value.0,
             key: String::from(value.1),
             authors,
+            names,
             fields,
+        })
+    }
+}
+
+/// A field of an entry whose value could not be parsed, see [`BibEntry::from_components`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidField {
+    /// the lowercased name of the field
+    pub field: String,
+    pub expected: String,
+}
+
+impl fmt::Display for InvalidField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expected)
+    }
+}
+
+impl std::error::Error for InvalidField {}
+
+fn name_list(field: &str, value: &str, langid: &Option<String>) -> Result<Vec<OwnedFullName>> {
+    if value.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let expected = format!("a list of names seperated by `and` in field `{}`", field);
+    match and_seperated_names(value) {
+        Ok((tail, names)) if tail.trim().is_empty() => Ok(names
+            .into_iter()
+            .map(|n| OwnedFullName {
+                langid: langid.clone(),
+                ..n.into()
+            })
+            .collect()),
+        // names that are only partly understood are reported rather than cut short
+        Ok((tail, _)) => Err(InvalidField {
+            field: field.to_string(),
+            expected: format!("{}, found `{}`", expected, tail.trim()),
+        }
+        .into()),
+        Err(_) => Err(InvalidField {
+            field: field.to_string(),
+            expected,
         }
+        .into()),
     }
 }
 
 fn entry_type(input: &str) -> IResult<&str, EntryType> {
-    let (tail, t) = alt((
-        tag_no_case("article"),
-        tag_no_case("booklet"),
-        tag_no_case("conference"),
-        tag_no_case("inbook"),
-        tag_no_case("book"),
-        tag_no_case("incollection"),
-        tag_no_case("inproceedings"),
-        tag_no_case("manual"),
-        tag_no_case("mastersthesis"),
-        tag_no_case("misc"),
-        tag_no_case("phdthesis"),
-        tag_no_case("proceedings"),
-        tag_no_case("techreport"),
-        tag_no_case("unpublished"),
-    ))(input)?;
-    let t = EntryType::try_from(t).expect("Unknown entry type");
-    Ok((tail, t))
+    let (tail, t) = take_while1(|c: char| c.is_alphabetic())(input)?;
+    Ok((tail, EntryType::from(t.to_lowercase().as_str())))
 }
 
+/// Field names are identifiers as BibTeX defines them: any run of printable
+/// characters that are not whitespace or one of `"#%'(),={}`. Any name is
+/// accepted here, it's up to the styles to decide which ones they use.
 fn field_type(input: &str) -> IResult<&str, &str> {
-    alt((
-        alt((
-            tag_no_case("address"),
-            tag_no_case("annote"),
-            tag_no_case("author"),
-            tag_no_case("booktitle"),
-            tag_no_case("chapter"),
-            tag_no_case("edition"),
-            tag_no_case("editor"),
-        )),
-        tag_no_case("howpublished"),
-        tag_no_case("institution"),
-        tag_no_case("journal"),
-        tag_no_case("month"),
-        tag_no_case("note"),
-        tag_no_case("number"),
-        tag_no_case("organization"),
-        tag_no_case("pages"),
-        tag_no_case("publisher"),
-        tag_no_case("school"),
-        tag_no_case("series"),
-        tag_no_case("title"),
-        tag_no_case("type"),
-        tag_no_case("volume"),
-        tag_no_case("year"),
-        tag_no_case("doi"),
-        tag_no_case("issn"),
-        tag_no_case("isbn"),
-        tag_no_case("url"),
-    ))(input)
+    verify(
+        take_while1(|c: char| !c.is_whitespace() && !"\"#%'(),={}".contains(c)),
+        |name: &str| !name.starts_with(|c: char| c.is_ascii_digit()),
+    )(input)
 }
 
 fn brace_quoted_field(input: &str) -> IResult<&str, &str> {
     delimited(tag("{"), take_until_unbalanced('{', '}'), tag("}"))(input)
 }
+/// Like BibTeX, a quote only ends the value outside of braces, so `"Schr{\"o}dinger"` is
+/// one value
 fn quote_quoted_field(input: &str) -> IResult<&str, &str> {
-    delimited(tag("\""), take_till(|c| c == '"'), tag("\""))(input)
+    delimited(tag("\""), until_unbraced_quote, tag("\""))(input)
 }
-fn unquoted_field(input: &str) -> IResult<&str, &str> {
-    let (tail, val) = take_till(|c| ",}".contains(c))(input)?;
-    let (_, val_stripped) = delimited(
-        many0(permutation((multispace0, line_ending))),
-        take_till(|c| is_space(c as u8)),
-        many0(permutation((multispace0, line_ending))),
-    )(val)?;
 
-    Ok((tail, val_stripped))
+fn until_unbraced_quote(input: &str) -> IResult<&str, &str> {
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '"' if depth == 0 => return Ok((&input[i..], &input[..i])),
+            _ => (),
+        }
+    }
+    Err(nom::Err::Error(Error::new(input, ErrorKind::TakeTill1)))
+}
+/// bare values are either numbers, which are taken literally,
+/// or the name of a string macro
+fn unquoted_field(input: &str) -> IResult<&str, ValuePart<'_>> {
+    let (tail, val) =
+        take_while1(|c: char| !c.is_whitespace() && !"\"#%'(),={}".contains(c))(input)?;
+    if val.chars().all(|c| c.is_ascii_digit()) {
+        Ok((tail, ValuePart::Literal(val)))
+    } else {
+        Ok((tail, ValuePart::Macro(val)))
+    }
 }
 
-fn field(input: &str) -> IResult<&str, (&str, &str)> {
+fn value_part(input: &str) -> IResult<&str, ValuePart<'_>> {
+    alt((
+        map(brace_quoted_field, ValuePart::Literal),
+        map(quote_quoted_field, ValuePart::Literal),
+        unquoted_field,
+    ))(input)
+}
+
+fn field_value(input: &str) -> IResult<&str, FieldValue<'_>> {
+    terminated(
+        separated_list1(delimited(multispace0, tag("#"), multispace0), value_part),
+        multispace0,
+    )(input)
+}
+
+fn field(input: &str) -> IResult<&str, (&str, FieldValue<'_>)> {
     let (tail, (kind, value)) = separated_pair(
         field_type,
         delimited(multispace0, tag("="), multispace0),
-        alt((brace_quoted_field, quote_quoted_field, unquoted_field)),
+        field_value,
     )(input)?;
     Ok((tail, (kind, value)))
 }
-fn fields(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
-    let (tail, fields) = separated_list1(
-        alt((
-            delimited(multispace0, tag(","), multispace0),
-            preceded(multispace0, line_ending),
-        )),
-        delimited(multispace0, field, multispace0),
-    )(input)?;
+/// fields are seperated by commas, though we are a little more lenient than
+/// bibtex and also accept fields that are only seperated by whitespace.
+fn fields(input: &str) -> IResult<&str, Vec<(&str, FieldValue<'_>)>> {
+    let (tail, fields) = many0(delimited(
+        multispace0,
+        field,
+        opt(delimited(multispace0, tag(","), multispace0)),
+    ))(input)?;
 
     Ok((tail, fields))
 }
@@ -267,20 +439,216 @@ fn entry_key(input: &str) -> IResult<&str, &str> {
     terminated(take_till1(|c| c == ','), char(','))(input)
 }
 
-pub fn entry(input: &str) -> IResult<&str, EntrySubComponents> {
+pub fn entry(input: &str) -> IResult<&str, EntrySubComponents<'_>> {
     let (tail, kind) = entry_kind(input)?;
     let (tail, content) = entry_content(tail)?;
     let (rest_of_content, key) = entry_key(content)?;
 
-    let (_, fields) = fields(rest_of_content)?;
+    let (_, fields) = all_consuming(terminated(fields, multispace0))(rest_of_content)?;
     let (tail, _) = multispace0(tail)?;
     Ok((tail, (kind, key, fields)))
 }
 
+/// parses `@string{name = value}`
+pub fn string_definition(input: &str) -> IResult<&str, (&str, FieldValue<'_>)> {
+    let (tail, (_, _, _, (name, value), _, _)) = tuple((
+        multispace0,
+        tag_no_case("@string"),
+        delimited(multispace0, tag("{"), multispace0),
+        field,
+        tag("}"),
+        multispace0,
+    ))(input)?;
+    Ok((tail, (name, value)))
+}
+
+/// parses `@preamble{value}`, the value can be concatenated just like fields
+pub fn preamble(input: &str) -> IResult<&str, FieldValue<'_>> {
+    delimited(
+        tuple((
+            multispace0,
+            tag_no_case("@preamble"),
+            delimited(multispace0, tag("{"), multispace0),
+        )),
+        field_value,
+        terminated(tag("}"), multispace0),
+    )(input)
+}
+
+/// parses `@comment{...}`. Like bibtex we also accept a bare `@comment`
+/// in which case the text after it is simply treated as free text.
+pub fn comment(input: &str) -> IResult<&str, &str> {
+    let (tail, _) = preceded(multispace0, tag_no_case("@comment"))(input)?;
+    match preceded(multispace0, brace_quoted_field)(tail) {
+        Ok((tail, content)) => Ok((tail, content)),
+        Err(_) => Ok((tail, "")),
+    }
+}
+
+pub fn bib_item(input: &str) -> IResult<&str, BibItem<'_>> {
+    alt((
+        map(comment, BibItem::Comment),
+        map(preamble, BibItem::Preamble),
+        map(string_definition, |(name, value)| {
+            BibItem::StringDefinition(name, value)
+        }),
+        map(entry, BibItem::Entry),
+    ))(input)
+}
+
+/// Parses all items in a bib file. Anything outside of an `@` item is ignored,
+/// just like bibtex does. If an item could not be parsed, the error points
+/// to where it went wrong.
+pub fn parse_bib(input: &str) -> Result<Vec<BibItem<'_>>, BibParseError> {
+    let (items, mut errors) = parse_bib_lenient(input);
+    if errors.is_empty() {
+        Ok(items)
+    } else {
+        Err(errors.remove(0))
+    }
+}
+
+/// Like [`parse_bib`] but items that could not be parsed are skipped instead.
+/// The errors for all the skipped items are returned alongside the parsed ones.
+pub fn parse_bib_lenient(input: &str) -> (Vec<BibItem<'_>>, Vec<BibParseError>) {
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find('@') {
+        let item = &rest[start..];
+        match bib_item(item) {
+            Ok((tail, parsed)) => {
+                items.push(parsed);
+                rest = tail;
+            }
+            Err(_) => {
+                errors.push(diagnose_item(input, item));
+                rest = skip_item(item);
+            }
+        }
+    }
+    (items, errors)
+}
+
+/// Skip over an item that could not be parsed. If it's braces are balanced we
+/// can skip the whole thing, otherwise we resume at the next line starting with an `@`
+fn skip_item(item: &str) -> &str {
+    let after_kind = item[1..].trim_start_matches(|c: char| c.is_alphabetic());
+    if let Ok((tail, _)) = preceded(multispace0, entry_content)(after_kind) {
+        return tail;
+    }
+    item.match_indices('\n')
+        .map(|(i, _)| item[i..].trim_start())
+        .find(|line| line.starts_with('@'))
+        .unwrap_or("")
+}
+
+/// The text of a parsed entry as it was written, from its `@` up to and including its
+/// closing brace. `key` is the key of the entry as parsed from `input`.
+pub fn entry_text<'a>(input: &'a str, key: &str) -> &'a str {
+    let start = input[..offset_in(input, key)].rfind('@').unwrap_or(0);
+    let item = &input[start..];
+    let after_kind = item[1..].trim_start_matches(|c: char| c.is_alphabetic());
+    match preceded(multispace0, entry_content)(after_kind) {
+        Ok((tail, _)) => &item[..item.len() - tail.len()],
+        Err(_) => item,
+    }
+}
+
+/// Work out why `item` (which starts with an `@`) could not be parsed, by
+/// going through it one step at a time
+fn diagnose_item(input: &str, item: &str) -> BibParseError {
+    let after_at = &item[1..];
+    let kind_len = after_at
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(after_at.len());
+    let (kind, after_kind) = after_at.split_at(kind_len);
+    if kind.is_empty() {
+        return BibParseError::new(input, after_at, "an entry type after `@`");
+    }
+    let is_string = kind.eq_ignore_ascii_case("string");
+    let is_preamble = kind.eq_ignore_ascii_case("preamble");
+
+    let (body, _) =
+        multispace0::<&str, nom::error::Error<&str>>(after_kind).unwrap_or((after_kind, ""));
+    let content = match entry_content(body) {
+        Ok((_, content)) => content,
+        Err(_) if !body.starts_with('{') => {
+            return BibParseError::new(input, body, format!("`{{` after `@{}`", kind))
+        }
+        Err(_) => return BibParseError::new(input, body, "a matching `}` for this `{`"),
+    };
+
+    if is_string {
+        return diagnose_fields(input, content)
+            .unwrap_or_else(|| BibParseError::new(input, content, "a single `name = value` pair"));
+    }
+    if is_preamble {
+        return BibParseError::new(
+            input,
+            content,
+            "a value: `{...}`, `\"...\"`, a number or a string macro",
+        );
+    }
+
+    let (fields_content, key) = match entry_key(content) {
+        Ok(res) => res,
+        Err(_) => return BibParseError::new(input, content, "a citation key followed by `,`"),
+    };
+    diagnose_fields(input, fields_content)
+        .unwrap_or_else(|| BibParseError::new(input, item, "a valid bib entry"))
+        .with_key(key.trim())
+}
+
+/// go through the fields one by one to find the first one that is malformed
+fn diagnose_fields(input: &str, content: &str) -> Option<BibParseError> {
+    let mut rest = content;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        let (after_name, name) = match field_type(rest) {
+            Ok(res) => res,
+            Err(_) => return Some(BibParseError::new(input, rest, "a field name")),
+        };
+        let after_eq = match delimited(
+            multispace0::<&str, nom::error::Error<&str>>,
+            tag("="),
+            multispace0,
+        )(after_name)
+        {
+            Ok((tail, _)) => tail,
+            Err(_) => {
+                return Some(BibParseError::new(
+                    input,
+                    after_name.trim_start(),
+                    format!("`=` after field name `{}`", name),
+                ))
+            }
+        };
+        rest = match field_value(after_eq) {
+            Ok((tail, _)) => tail,
+            Err(_) => {
+                return Some(BibParseError::new(
+                    input,
+                    after_eq,
+                    format!(
+                        "a value for field `{}`: `{{...}}`, `\"...\"`, a number or a string macro",
+                        name
+                    ),
+                ))
+            }
+        };
+        rest = rest.strip_prefix(',').unwrap_or(rest);
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::parsing::names::NameKind;
     use anyhow::Result;
 
     #[test]
@@ -292,6 +660,26 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn quotes_in_braces_dont_end_a_quoted_field() -> Result<()> {
+        let input = r#"author = "Schr{\"o}dinger, Erwin", year = 1935"#;
+        let (tail, (kind, content)) = field(input)?;
+        assert_eq!(tail, ", year = 1935");
+        assert_eq!(kind, "author");
+        assert_eq!(
+            content,
+            vec![ValuePart::Literal(r#"Schr{\"o}dinger, Erwin"#)]
+        );
+
+        let contents = format!("@misc{{foo, {}}}", input);
+        let entry = match parse_bib(&contents)?.remove(0) {
+            BibItem::Entry(components) => BibEntry::try_from(components)?,
+            _ => unreachable!(),
+        };
+        assert_eq!(entry.authors[0].last, vec!["Schrödinger"]);
+        Ok(())
+    }
+
     #[test]
     fn entry_debug_fmt() -> Result<()> {
         let mut dict: BTreeMap<String, String> = BTreeMap::new();
@@ -309,25 +697,49 @@ mod test {
                     first: vec!["Ada".to_string(), "Maria".to_string()],
                     last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    langid: None,
                     title: vec![],
                 },
                 OwnedFullName {
                     first: vec!["Amalie".to_string(), "Emmy".to_string()],
                     last: vec!["Noether".to_string()],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    langid: None,
                     title: vec![],
                 },
             ],
+            names: BTreeMap::new(),
             fields: dict,
         };
-        assert_eq!(format!("{:?}", test_entry), "foo(Article)\n  - Authors:\n    - First(Ada Maria) Von() Last(Lovelace Augusta) Title()\n    - First(Amalie Emmy) Von() Last(Noether) Title()\n  - title = The little mathematician that could.\n  - volume = one\n  - year = 1984\n".to_string());
+        assert_eq!(format!("{:?}", test_entry), "foo(Article)\n  - Authors:\n    - First(Ada Maria) Von() Last(Lovelace Augusta) Jr() Title()\n    - First(Amalie Emmy) Von() Last(Noether) Jr() Title()\n  - title = The little mathematician that could.\n  - volume = one\n  - year = 1984\n".to_string());
         Ok(())
     }
 
     #[test]
-    fn error_on_unknown_entry_type() -> Result<()> {
-        let res = EntryType::try_from("asdflkj;as");
-        assert!(res.is_err());
+    fn name_list_fields() -> Result<()> {
+        let (_, components) = entry(
+            "@book{foo, author = {Jane Doe}, editor = {Smith, John and Ann Jones}, translator = {}, title = {Foo}}",
+        )
+        .unwrap();
+        let entry = BibEntry::try_from(components)?;
+        assert_eq!(entry.authors[0].last, vec!["Doe"]);
+        assert_eq!(entry.names["editor"].len(), 2);
+        assert_eq!(entry.names["editor"][0].first, vec!["John"]);
+        assert_eq!(entry.names["editor"][1].last, vec!["Jones"]);
+        assert!(entry.names["translator"].is_empty());
+        assert!(!entry.fields.contains_key("editor"));
+        assert!(!entry.fields.contains_key("translator"));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_entry_type_is_other() -> Result<()> {
+        let res = EntryType::from("asdflkj;as");
+        assert_eq!(res, EntryType::Other("asdflkj;as".to_string()));
         Ok(())
     }
     #[test]
@@ -372,7 +784,7 @@ mod test {
 
         assert_eq!(kind, EntryType::Misc);
         assert_eq!(key, "foo");
-        assert_eq!(fields, vec![("title", "blurb")]);
+        assert_eq!(fields, vec![("title", vec![ValuePart::Literal("blurb")])]);
         Ok(())
     }
     #[test]
@@ -448,12 +860,60 @@ mod test {
         Ok(())
     }
     #[test]
+    fn test_unknown_field_type_parsing() -> Result<()> {
+        for (test, expected) in [
+            ("abstract = ", "abstract"),
+            ("keywords=", "keywords"),
+            ("archivePrefix =", "archivePrefix"),
+            ("urldate", "urldate"),
+            ("mendeley-groups", "mendeley-groups"),
+            ("file ", "file"),
+        ] {
+            let (_tail, field) = field_type(test)?;
+            assert_eq!(field, expected);
+        }
+        assert!(field_type("2001").is_err());
+        assert!(field_type("= {foo}").is_err());
+
+        Ok(())
+    }
+    #[test]
+    fn entry_with_unknown_fields() -> Result<()> {
+        let input = "@article{zotero,
+        title = {Random forests},
+        Abstract = {Random forests are a combination of tree predictors},
+        keywords = {classification, regression},
+        file = {Full Text:files/1/breiman.pdf:application/pdf},
+        eprint = {1234.5678},
+        archivePrefix = {arXiv},
+        language = {en},
+        urldate = {2024-05-01},
+        }";
+        let (tail, components) = entry(input)?;
+        assert_eq!(tail, "");
+        let entry = BibEntry::try_from(components)?;
+        assert_eq!(entry.fields.len(), 8);
+        assert_eq!(
+            entry.fields.get("abstract").map(|s| s.as_str()),
+            Some("Random forests are a combination of tree predictors")
+        );
+        assert_eq!(
+            entry.fields.get("archiveprefix").map(|s| s.as_str()),
+            Some("arXiv")
+        );
+        assert_eq!(
+            entry.fields.get("urldate").map(|s| s.as_str()),
+            Some("2024-05-01")
+        );
+        Ok(())
+    }
+    #[test]
     fn unquoted_year_comma() -> Result<()> {
         let input = "year    = 1956   ,  \n";
         let (tail, (kind, content)) = field(input)?;
         assert_eq!(tail, ",  \n");
         assert_eq!(kind, "year");
-        assert_eq!(content, "1956");
+        assert_eq!(content, vec![ValuePart::Literal("1956")]);
 
         Ok(())
     }
@@ -463,7 +923,7 @@ mod test {
         let (tail, (kind, content)) = field(input)?;
         assert_eq!(tail, "}");
         assert_eq!(kind, "year");
-        assert_eq!(content, "1956");
+        assert_eq!(content, vec![ValuePart::Literal("1956")]);
 
         Ok(())
     }
@@ -473,7 +933,7 @@ mod test {
         let (tail, (kind, content)) = field(input)?;
         assert_eq!(tail, "}");
         assert_eq!(kind, "month");
-        assert_eq!(content, "jun");
+        assert_eq!(content, vec![ValuePart::Macro("jun")]);
 
         Ok(())
     }
@@ -483,30 +943,212 @@ mod test {
         let (tail, (kind, content)) = field(input)?;
         assert_eq!(tail, ", month   = {jun}");
         assert_eq!(kind, "month");
-        assert_eq!(content, "jun");
+        assert_eq!(content, vec![ValuePart::Literal("jun")]);
 
         Ok(())
     }
 
     #[test]
-    fn text_citation_simple() -> Result<()> {
-        let input = "         asd;lkfjwliefjxcajvnasledifm; sei help I'm stuck in a sub factory! asdoifmwae;va \\cite{book}.lkfjwliefjxcajvnasledifm";
-        let (tail, (unmodified, citation_key)) = next_citation(input)?;
+    fn concatenated_field() -> Result<()> {
+        let input = "journal = jmlr # \" (Special Issue)\" # {, Part } #2,";
+        let (tail, (kind, content)) = field(input)?;
+        assert_eq!(tail, ",");
+        assert_eq!(kind, "journal");
+        assert_eq!(
+            content,
+            vec![
+                ValuePart::Macro("jmlr"),
+                ValuePart::Literal(" (Special Issue)"),
+                ValuePart::Literal(", Part "),
+                ValuePart::Literal("2"),
+            ]
+        );
 
-        assert_eq!(tail, ".lkfjwliefjxcajvnasledifm");
-        assert_eq!(unmodified,"         asd;lkfjwliefjxcajvnasledifm; sei help I'm stuck in a sub factory! asdoifmwae;va ");
-        assert_eq!(citation_key, "book");
+        Ok(())
+    }
+    #[test]
+    fn parse_string_definition() -> Result<()> {
+        let input = "@string{jmlr = \"Journal of Machine Learning Research\"}\n@misc{foo,}";
+        let (tail, item) = bib_item(input)?;
+        assert_eq!(tail, "@misc{foo,}");
+        assert_eq!(
+            item,
+            BibItem::StringDefinition(
+                "jmlr",
+                vec![ValuePart::Literal("Journal of Machine Learning Research")]
+            )
+        );
+        Ok(())
+    }
+    #[test]
+    fn expand_macros() -> Result<()> {
+        let mut macros = StringMacros::new();
+        macros.insert("jmlr".to_string(), "JMLR".to_string());
+        let value = vec![
+            ValuePart::Macro("JMLR"),
+            ValuePart::Literal(" & "),
+            ValuePart::Macro("nips"),
+        ];
+        assert_eq!(expand_field_value(&value, &macros), "JMLR & nips");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_comment_and_preamble() -> Result<()> {
+        let input = "@Comment{jabref-meta: databaseType:bibtex;}";
+        let (tail, item) = bib_item(input)?;
+        assert_eq!(tail, "");
+        assert_eq!(item, BibItem::Comment("jabref-meta: databaseType:bibtex;"));
+
+        let input = "@comment this is an old style comment";
+        let (tail, item) = bib_item(input)?;
+        assert_eq!(tail, " this is an old style comment");
+        assert_eq!(item, BibItem::Comment(""));
+
+        let input = "@PREAMBLE{ \"\\newcommand{\\noopsort}[1]{}\" # foo }\n";
+        let (tail, item) = bib_item(input)?;
+        assert_eq!(tail, "");
+        assert_eq!(
+            item,
+            BibItem::Preamble(vec![
+                ValuePart::Literal("\\newcommand{\\noopsort}[1]{}"),
+                ValuePart::Macro("foo")
+            ])
+        );
+        Ok(())
+    }
+    #[test]
+    fn skip_free_text() -> Result<()> {
+        let input = "This file was created by hand.
+        Please don't touch it.
+
+        @misc{foo,
+        title = {blurb},
+        }
+        some notes about foo
+
+        @comment{jabref-meta: grouping:
+        0 AllEntriesGroup:;
+        }
+        trailing text";
+        let items = parse_bib(input)?;
+        assert_eq!(items.len(), 2);
+        assert!(matches!(
+            items[0],
+            BibItem::Entry((EntryType::Misc, "foo", _))
+        ));
+        assert!(matches!(items[1], BibItem::Comment(_)));
         Ok(())
     }
 
     #[test]
-    fn book_example() -> Result<()> {
-        let input =
-            "#cite \r\n\r\n[^cms]: \\cite{cms}\r\n[^ieee]: \\cite{ieee}\r\n[^doi]: \\cite{doi}\r\n";
-        let (tail, (_unmodified, citation_key)) = next_citation(input)?;
+    fn parse_entry_without_fields() -> Result<()> {
+        let (tail, (kind, key, fields)) = entry("@misc{foo,}")?;
+        assert_eq!(tail, "");
+        assert_eq!(kind, EntryType::Misc);
+        assert_eq!(key, "foo");
+        assert!(fields.is_empty());
+        Ok(())
+    }
+    #[test]
+    fn parse_fields_without_commas() -> Result<()> {
+        let (tail, (_kind, _key, fields)) = entry("@misc{foo,\n title = {bar}\n year = 2001\n}")?;
+        assert_eq!(tail, "");
+        assert_eq!(
+            fields,
+            vec![
+                ("title", vec![ValuePart::Literal("bar")]),
+                ("year", vec![ValuePart::Literal("2001")])
+            ]
+        );
+        Ok(())
+    }
+
+    fn parse_error(input: &str) -> BibParseError {
+        parse_bib(input).expect_err("input should not parse")
+    }
 
-        assert_eq!(tail, "\r\n[^ieee]: \\cite{ieee}\r\n[^doi]: \\cite{doi}\r\n");
-        assert_eq!(citation_key, "cms");
+    #[test]
+    fn biblatex_and_unknown_entry_types() {
+        for (kind, expected) in [
+            ("online", EntryType::Online),
+            ("WWW", EntryType::Online),
+            ("electronic", EntryType::Online),
+            ("software", EntryType::Software),
+            ("dataset", EntryType::Dataset),
+            ("report", EntryType::Report),
+            ("thesis", EntryType::Thesis),
+            ("patent", EntryType::Patent),
+            ("collection", EntryType::Collection),
+            ("mvbook", EntryType::Mvbook),
+            ("periodical", EntryType::Periodical),
+            ("standard", EntryType::Standard),
+            ("artcle", EntryType::Other("artcle".to_string())),
+        ] {
+            let input = format!("@{}{{foo,\n title = {{b}},\n}}", kind);
+            let (_, (parsed, key, _)) = entry(&input).unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(key, "foo");
+        }
+    }
+    #[test]
+    fn error_missing_equals() {
+        let err = parse_error("@misc{foo,\n  title {bar},\n}");
+        assert_eq!((err.line, err.column), (2, 9));
+        assert_eq!(err.expected, "`=` after field name `title`");
+        assert_eq!(err.key, Some("foo".to_string()));
+        assert_eq!(err.snippet, "  title {bar},");
+    }
+    #[test]
+    fn error_missing_value() {
+        let err = parse_error("@misc{foo,\n  title = ,\n}");
+        assert_eq!((err.line, err.column), (2, 11));
+        assert!(err.expected.starts_with("a value for field `title`"));
+    }
+    #[test]
+    fn error_unbalanced_braces() {
+        let err = parse_error("@misc{foo,\n  title = {bar,\n}");
+        assert_eq!((err.line, err.column), (1, 6));
+        assert_eq!(err.expected, "a matching `}` for this `{`");
+    }
+    #[test]
+    fn error_missing_key() {
+        let err = parse_error("@misc{title = {bar}}");
+        assert_eq!((err.line, err.column), (1, 7));
+        assert_eq!(err.expected, "a citation key followed by `,`");
+    }
+    #[test]
+    fn lenient_parsing_skips_bad_items() {
+        let input = "@misc{foo,\n  title {bar},\n}
+        @misc{good,
+          title = {Good},
+        }
+        @misc{unbalanced,
+          title = {oops,
+        }
+        @misc{better, title = {Better}}
+        @misc{bad, year = }";
+        let (items, errors) = parse_bib_lenient(input);
+        let keys: Vec<&str> = items
+            .iter()
+            .filter_map(|i| match i {
+                BibItem::Entry((_, key, _)) => Some(*key),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(keys, vec!["good", "better"]);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| (e.key.as_deref(), e.line))
+                .collect::<Vec<_>>(),
+            vec![(Some("foo"), 2), (None, 7), (Some("bad"), 11)]
+        );
+    }
+    #[test]
+    fn error_unparsable_author() -> Result<()> {
+        let (_, components) = entry("@misc{foo, author = {,}}")?;
+        assert!(BibEntry::try_from(components).is_err());
         Ok(())
     }
 }
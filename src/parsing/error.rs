@@ -0,0 +1,128 @@
+use std::{fmt, path::PathBuf};
+
+/// An error encountered while parsing a bib file.
+/// It points to where in the file things went wrong and what was expected there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BibParseError {
+    pub file: Option<PathBuf>,
+    pub key: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    /// the line of the input on which the error occurred
+    pub snippet: String,
+    pub expected: String,
+}
+
+impl BibParseError {
+    /// Create an error located at the start of `remaining`, which should be a subslice of `input`.
+    /// If it isn't, the error is located at the end of the input.
+    pub fn new(input: &str, remaining: &str, expected: impl Into<String>) -> Self {
+        let offset = offset_in(input, remaining);
+
+        let line_start = input[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = input[offset..]
+            .find('\n')
+            .map(|i| i + offset)
+            .unwrap_or(input.len());
+
+        Self {
+            file: None,
+            key: None,
+            line: line_number(input, remaining),
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: input[line_start..line_end].trim_end().to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn with_key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    pub fn with_file(mut self, file: PathBuf) -> Self {
+        self.file = Some(file);
+        self
+    }
+}
+
+impl fmt::Display for BibParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(
+            f,
+            "{}:{}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        if let Some(key) = &self.key {
+            write!(f, " in entry `{}`", key)?;
+        }
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(
+            f,
+            "{} | {}^",
+            gutter,
+            " ".repeat(self.column.saturating_sub(1))
+        )
+    }
+}
+
+impl std::error::Error for BibParseError {}
+
+/// the offset of `remaining` in `input`, or the end of the input if it isn't a subslice of it
+pub(crate) fn offset_in(input: &str, remaining: &str) -> usize {
+    let start = input.as_ptr() as usize;
+    (remaining.as_ptr() as usize)
+        .checked_sub(start)
+        .filter(|o| *o <= input.len())
+        .unwrap_or(input.len())
+}
+
+/// The (1 based) line of `input` that `remaining` starts on
+pub fn line_number(input: &str, remaining: &str) -> usize {
+    input[..offset_in(input, remaining)].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn location_of_error() {
+        let input = "@misc{foo,\n  title {bar},\n}";
+        let remaining = &input[19..];
+        let err = BibParseError::new(input, remaining, "`=` after field name `title`");
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 9);
+        assert_eq!(err.snippet, "  title {bar},");
+    }
+
+    #[test]
+    fn display_error() {
+        let input = "@misc{foo,\n  title {bar},\n}";
+        let remaining = &input[19..];
+        let err = BibParseError::new(input, remaining, "`=` after field name `title`")
+            .with_key("foo")
+            .with_file(PathBuf::from("refs.bib"));
+        assert_eq!(
+            err.to_string(),
+            "refs.bib:2:9: expected `=` after field name `title` in entry `foo`
+  |
+2 |   title {bar},
+  |         ^"
+        );
+    }
+
+    #[test]
+    fn error_at_end_of_input() {
+        let input = "@misc{foo,";
+        let err = BibParseError::new(input, "", "a closing `}`");
+        assert_eq!(err.line, 1);
+        assert_eq!(err.column, 11);
+    }
+}
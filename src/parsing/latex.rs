@@ -0,0 +1,277 @@
+use nom::{
+    branch::alt,
+    bytes::complete::take_while1,
+    character::complete::{char, one_of, satisfy, space0},
+    combinator::{recognize, verify},
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+use parse_hyperlinks::take_until_unbalanced;
+use unicode_normalization::UnicodeNormalization;
+
+/// accents that are written with a symbol, e.g. `\"o`
+const SYMBOL_ACCENTS: &str = "\"'`^~=.";
+/// accents that are written with a letter, e.g. `\c{c}`
+const LETTER_ACCENTS: &[&str] = &["c", "u", "v", "H", "k", "r", "d", "b", "t"];
+
+fn combining_char(accent: &str) -> Option<char> {
+    match accent {
+        "`" => Some('\u{300}'),
+        "'" => Some('\u{301}'),
+        "^" => Some('\u{302}'),
+        "~" => Some('\u{303}'),
+        "=" => Some('\u{304}'),
+        "u" => Some('\u{306}'),
+        "." => Some('\u{307}'),
+        "\"" => Some('\u{308}'),
+        "r" => Some('\u{30A}'),
+        "H" => Some('\u{30B}'),
+        "v" => Some('\u{30C}'),
+        "d" => Some('\u{323}'),
+        "c" => Some('\u{327}'),
+        "k" => Some('\u{328}'),
+        "b" => Some('\u{331}'),
+        "t" => Some('\u{361}'),
+        _ => None,
+    }
+}
+
+/// characters like ligatures and foreign letters that have their own command
+fn special_char(command: &str) -> Option<&'static str> {
+    match command {
+        "ss" => Some("ß"),
+        "SS" => Some("SS"),
+        "aa" => Some("å"),
+        "AA" => Some("Å"),
+        "ae" => Some("æ"),
+        "AE" => Some("Æ"),
+        "oe" => Some("œ"),
+        "OE" => Some("Œ"),
+        "o" => Some("ø"),
+        "O" => Some("Ø"),
+        "l" => Some("ł"),
+        "L" => Some("Ł"),
+        "i" => Some("ı"),
+        "j" => Some("ȷ"),
+        "dh" => Some("ð"),
+        "DH" => Some("Ð"),
+        "th" => Some("þ"),
+        "TH" => Some("Þ"),
+        "ng" => Some("ŋ"),
+        "NG" => Some("Ŋ"),
+        "textendash" => Some("–"),
+        "textemdash" => Some("—"),
+        _ => None,
+    }
+}
+
+fn command_name(input: &str) -> IResult<&str, &str> {
+    preceded(char('\\'), take_while1(|c: char| c.is_ascii_alphabetic()))(input)
+}
+
+fn accent_argument(input: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(char('{'), take_until_unbalanced('{', '}'), char('}')),
+        recognize(command_name),
+        recognize(satisfy(|c| c.is_alphanumeric())),
+    ))(input)
+}
+
+/// Recognizes a single LaTeX special character such as `{\"o}`, `\'{e}` or `{\ss}`
+/// without decoding it, so it can be used as part of a word.
+pub fn latex_special_char(input: &str) -> IResult<&str, &str> {
+    alt((
+        // a brace group starting with a command is a "special character" in bibtex
+        recognize(verify(
+            delimited(char('{'), take_until_unbalanced('{', '}'), char('}')),
+            is_special_group,
+        )),
+        recognize(tuple((char('\\'), one_of(SYMBOL_ACCENTS), accent_argument))),
+        recognize(pair(
+            verify(command_name, |c: &str| LETTER_ACCENTS.contains(&c)),
+            preceded(space0, accent_argument),
+        )),
+        recognize(verify(command_name, |c: &str| special_char(c).is_some())),
+    ))(input)
+}
+
+/// whether the contents of a brace group make it a special character like `{\\"o}`
+fn is_special_group(inner: &str) -> bool {
+    inner.starts_with('\\') && decode_command(inner).is_some()
+}
+
+fn apply_accent(accent: &str, argument: &str) -> String {
+    let mut base = decode_latex(argument);
+    // accented i's and j's are often written with the dotless variants
+    base = base.replace('ı', "i").replace('ȷ', "j");
+    match combining_char(accent) {
+        Some(combining) => {
+            let mut chars = base.chars();
+            match chars.next() {
+                Some(first) => std::iter::once(first)
+                    .chain(std::iter::once(combining))
+                    .chain(chars)
+                    .nfc()
+                    .collect(),
+                None => combining.to_string(),
+            }
+        }
+        None => base,
+    }
+}
+
+/// Decode a single command at the start of `input`, which starts with a `\`.
+/// Returns the decoded text and the remaining input, or None if the command
+/// isn't one we know, in which case it should be left as is.
+fn decode_command(input: &str) -> Option<(String, &str)> {
+    let mut chars = input[1..].chars();
+    let next = chars.next()?;
+    if SYMBOL_ACCENTS.contains(next) {
+        let rest = &input[1 + next.len_utf8()..];
+        let (tail, arg) = accent_argument(rest).ok()?;
+        return Some((apply_accent(&next.to_string(), arg), tail));
+    }
+    // `\$`, `\{` and `\}` are left for the markup parser, see [`crate::parsing::markup`]
+    if "&%#_".contains(next) {
+        return Some((next.to_string(), &input[2..]));
+    }
+    let (tail, name) = command_name(input).ok()?;
+    if name == "url" {
+        // urls are taken verbatim
+        let (tail, _) = preceded(space0, accent_argument)(tail).ok()?;
+        return Some((input[..input.len() - tail.len()].to_string(), tail));
+    }
+    if LETTER_ACCENTS.contains(&name) {
+        let (tail, arg) = preceded(space0, accent_argument)(tail).ok()?;
+        return Some((apply_accent(name, arg), tail));
+    }
+    // like latex, control words eat the whitespace after them
+    special_char(name).map(|s| (s.to_string(), tail.trim_start_matches([' ', '\t'])))
+}
+
+/// Converts LaTeX accents, ligatures and special characters, dashes and
+/// non-breaking spaces into their unicode equivalent. Braces that do not surround a special
+/// character and commands that aren't known are left untouched.
+pub fn decode_latex(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\\' => match decode_command(rest) {
+                Some((decoded, tail)) => {
+                    out.push_str(&decoded);
+                    rest = tail;
+                }
+                None => {
+                    // keep the command and its name so we don't interpret its
+                    // name as anything else
+                    let len = command_name(rest)
+                        .map(|(tail, _)| rest.len() - tail.len())
+                        .unwrap_or(1 + rest[1..].chars().next().map_or(0, |c| c.len_utf8()));
+                    out.push_str(&rest[..len]);
+                    rest = &rest[len..];
+                }
+            },
+            '{' => match delimited(char('{'), take_until_unbalanced('{', '}'), char('}'))(rest) {
+                Ok((tail, inner)) => {
+                    if is_special_group(inner) {
+                        out.push_str(&decode_latex(inner));
+                    } else {
+                        out.push('{');
+                        out.push_str(&decode_latex(inner));
+                        out.push('}');
+                    }
+                    rest = tail;
+                }
+                Err(_) => {
+                    out.push(c);
+                    rest = &rest[1..];
+                }
+            },
+            '~' => {
+                out.push('\u{a0}');
+                rest = &rest[1..];
+            }
+            '-' => {
+                let dashes = rest.len() - rest.trim_start_matches('-').len();
+                match dashes {
+                    2 => out.push('–'),
+                    3 => out.push('—'),
+                    n => out.push_str(&rest[..n]),
+                }
+                rest = &rest[dashes..];
+            }
+            _ => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accents() {
+        assert_eq!(decode_latex("Schr{\\\"o}dinger"), "Schrödinger");
+        assert_eq!(decode_latex("Schr\\\"odinger"), "Schrödinger");
+        assert_eq!(decode_latex("Caf\\'{e}"), "Café");
+        assert_eq!(decode_latex("Fran\\c{c}ois"), "François");
+        assert_eq!(decode_latex("Fran\\c cois"), "François");
+        assert_eq!(decode_latex("{\\v{S}}koda"), "Škoda");
+        assert_eq!(decode_latex("Erd\\H{o}s"), "Erdős");
+        assert_eq!(decode_latex("Na\\\"{\\i}ve"), "Naïve");
+        assert_eq!(
+            decode_latex("\\`a \\^e \\~n \\=o \\.z \\u{g} \\k{a} \\r{u}"),
+            "à ê ñ ō ż ğ ą ů"
+        );
+    }
+
+    #[test]
+    fn special_characters() {
+        assert_eq!(decode_latex("Stra{\\ss}e"), "Straße");
+        assert_eq!(decode_latex("Stra\\ss e"), "Straße");
+        assert_eq!(decode_latex("{\\AA}ngstr{\\\"o}m"), "Ångström");
+        assert_eq!(decode_latex("{\\ae} {\\oe} {\\o} {\\l}"), "æ œ ø ł");
+        assert_eq!(decode_latex("Tom \\& Jerry 100\\%"), "Tom & Jerry 100%");
+    }
+
+    #[test]
+    fn dashes_and_spaces() {
+        assert_eq!(decode_latex("5--32"), "5–32");
+        assert_eq!(decode_latex("yes---no"), "yes—no");
+        assert_eq!(decode_latex("well-known"), "well-known");
+        assert_eq!(decode_latex("Fig.~1"), "Fig.\u{a0}1");
+    }
+
+    #[test]
+    fn leaves_other_braces_and_commands_alone() {
+        assert_eq!(
+            decode_latex("The {NASA} mission to {M\\\"{a}rs}"),
+            "The {NASA} mission to {Märs}"
+        );
+        assert_eq!(decode_latex("\\emph{Foo}"), "\\emph{Foo}");
+        assert_eq!(decode_latex("\\url{a--b/~c}"), "\\url{a--b/~c}");
+        assert_eq!(decode_latex("\\$5 \\{x\\}"), "\\$5 \\{x\\}");
+        assert_eq!(decode_latex("\\unknown"), "\\unknown");
+        assert_eq!(decode_latex("{\\em No} Starch"), "{\\em No} Starch");
+        assert_eq!(decode_latex("unbalanced {"), "unbalanced {");
+    }
+
+    #[test]
+    fn recognize_special_chars() {
+        assert_eq!(
+            latex_special_char("{\\\"o}dinger"),
+            Ok(("dinger", "{\\\"o}"))
+        );
+        assert_eq!(latex_special_char("\\'{e} foo"), Ok((" foo", "\\'{e}")));
+        assert_eq!(latex_special_char("\\c{c}ois"), Ok(("ois", "\\c{c}")));
+        assert_eq!(latex_special_char("\\ss"), Ok(("", "\\ss")));
+        assert!(latex_special_char("\\emph{foo}").is_err());
+        assert!(latex_special_char("{NASA}").is_err());
+        assert!(latex_special_char("{\\em NASA}").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till1, take_while1},
+    character::complete::{char, multispace0, space0},
+    combinator::{map, verify},
+    sequence::{delimited, preceded, terminated, tuple},
+    IResult,
+};
+use parse_hyperlinks::take_until_unbalanced;
+
+/// Inline formatting in a field value, as written with LaTeX commands.
+/// Stylizers hand this to a [`crate::formaters::Formatter`] to render it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    Text(String),
+    /// `\emph{}`, `\textit{}` or `{\em }`
+    Italic(Vec<Inline>),
+    /// `\textbf{}` or `{\bf }`
+    Bold(Vec<Inline>),
+    /// `\texttt{}` or `{\tt }`
+    Verbatim(String),
+    /// `$...$`
+    Math(String),
+    /// `\url{}`
+    Url(String),
+    /// text in braces, whose case should not be changed
+    Protected(Vec<Inline>),
+}
+
+const ITALIC_COMMANDS: &[&str] = &["emph", "textit", "textsl"];
+const BOLD_COMMANDS: &[&str] = &["textbf"];
+const VERBATIM_COMMANDS: &[&str] = &["texttt"];
+const URL_COMMANDS: &[&str] = &["url"];
+/// commands that don't change how we render their contents
+const PLAIN_COMMANDS: &[&str] = &["textrm", "textsc", "textup", "textnormal", "textmd", "mbox"];
+
+fn braced(input: &str) -> IResult<&str, &str> {
+    delimited(char('{'), take_until_unbalanced('{', '}'), char('}'))(input)
+}
+
+fn command<'a>(names: &'static [&'static str]) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    preceded(
+        char('\\'),
+        verify(take_while1(|c: char| c.is_ascii_alphabetic()), |n: &str| {
+            names.contains(&n)
+        }),
+    )
+}
+
+/// a command with a single argument, e.g. `\emph{foo}`
+fn argument<'a>(
+    names: &'static [&'static str],
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    preceded(terminated(command(names), space0), braced)
+}
+
+/// old style switches that apply to the rest of the group, e.g. `{\em foo}`
+fn switch<'a>(names: &'static [&'static str]) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    delimited(
+        char('{'),
+        preceded(
+            tuple((command(names), multispace0)),
+            take_until_unbalanced('{', '}'),
+        ),
+        char('}'),
+    )
+}
+
+fn formatted(input: &str) -> IResult<&str, Vec<Inline>> {
+    alt((
+        map(argument(ITALIC_COMMANDS), |i| {
+            vec![Inline::Italic(parse_markup(i))]
+        }),
+        map(switch(&["em", "it", "sl"]), |i| {
+            vec![Inline::Italic(parse_markup(i))]
+        }),
+        map(argument(BOLD_COMMANDS), |i| {
+            vec![Inline::Bold(parse_markup(i))]
+        }),
+        map(switch(&["bf"]), |i| vec![Inline::Bold(parse_markup(i))]),
+        map(argument(VERBATIM_COMMANDS), |i| {
+            vec![Inline::Verbatim(plain_text(&parse_markup(i)))]
+        }),
+        map(switch(&["tt"]), |i| {
+            vec![Inline::Verbatim(plain_text(&parse_markup(i)))]
+        }),
+        map(argument(URL_COMMANDS), |i| vec![Inline::Url(i.to_string())]),
+        map(argument(PLAIN_COMMANDS), parse_markup),
+        map(
+            delimited(tag("$"), take_till1(|c| c == '$'), tag("$")),
+            |m: &str| vec![Inline::Math(m.to_string())],
+        ),
+    ))(input)
+}
+
+fn push_text(nodes: &mut Vec<Inline>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match nodes.last_mut() {
+        Some(Inline::Text(t)) => t.push_str(text),
+        _ => nodes.push(Inline::Text(text.to_string())),
+    }
+}
+
+fn push_nodes(nodes: &mut Vec<Inline>, new: Vec<Inline>) {
+    for node in new {
+        match node {
+            Inline::Text(t) => push_text(nodes, &t),
+            n => nodes.push(n),
+        }
+    }
+}
+
+/// Parse the LaTeX formatting commands in a field value. Braces protect their
+/// contents, anything else that isn't a formatting command is kept as text.
+pub fn parse_markup(input: &str) -> Vec<Inline> {
+    let mut nodes = Vec::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        if let Ok((tail, new)) = formatted(rest) {
+            push_nodes(&mut nodes, new);
+            rest = tail;
+            continue;
+        }
+        match c {
+            '{' => match braced(rest) {
+                Ok((tail, inner)) => {
+                    nodes.push(Inline::Protected(parse_markup(inner)));
+                    rest = tail;
+                }
+                Err(_) => {
+                    push_text(&mut nodes, "{");
+                    rest = &rest[1..];
+                }
+            },
+            // escaped characters that would otherwise be markup
+            '\\' if rest[1..].starts_with(['$', '{', '}']) => {
+                push_text(&mut nodes, &rest[1..2]);
+                rest = &rest[2..];
+            }
+            _ => {
+                push_text(&mut nodes, &rest[..c.len_utf8()]);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    nodes
+}
+
+/// The text of the nodes without any of the formatting
+pub fn plain_text(nodes: &[Inline]) -> String {
+    nodes
+        .iter()
+        .map(|n| match n {
+            Inline::Text(t) | Inline::Verbatim(t) | Inline::Math(t) | Inline::Url(t) => t.clone(),
+            Inline::Italic(c) | Inline::Bold(c) | Inline::Protected(c) => plain_text(c),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(t: &str) -> Inline {
+        Inline::Text(t.to_string())
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(parse_markup("Random forests"), vec![text("Random forests")]);
+        assert_eq!(parse_markup(""), vec![]);
+    }
+
+    #[test]
+    fn italics_and_bold() {
+        assert_eq!(
+            parse_markup("Growth of \\emph{E. coli} in \\textbf{bold \\textit{and} italic}"),
+            vec![
+                text("Growth of "),
+                Inline::Italic(vec![text("E. coli")]),
+                text(" in "),
+                Inline::Bold(vec![
+                    text("bold "),
+                    Inline::Italic(vec![text("and")]),
+                    text(" italic")
+                ]),
+            ]
+        );
+        assert_eq!(
+            parse_markup("{\\em Homo sapiens} and {\\bf strong}"),
+            vec![
+                Inline::Italic(vec![text("Homo sapiens")]),
+                text(" and "),
+                Inline::Bold(vec![text("strong")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn verbatim_math_and_urls() {
+        assert_eq!(
+            parse_markup(
+                "The \\texttt{cite-me-bro} crate, $O(n^2)$, see \\url{https://example.com/~a}"
+            ),
+            vec![
+                text("The "),
+                Inline::Verbatim("cite-me-bro".to_string()),
+                text(" crate, "),
+                Inline::Math("O(n^2)".to_string()),
+                text(", see "),
+                Inline::Url("https://example.com/~a".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn braces_protect_and_other_commands_are_text() {
+        assert_eq!(
+            parse_markup("The {NASA {\\emph{Mars}} mission} costs \\$5 \\unknown{x}"),
+            vec![
+                text("The "),
+                Inline::Protected(vec![
+                    text("NASA "),
+                    Inline::Protected(vec![Inline::Italic(vec![text("Mars")])]),
+                    text(" mission")
+                ]),
+                text(" costs $5 \\unknown"),
+                Inline::Protected(vec![text("x")]),
+            ]
+        );
+        assert_eq!(
+            parse_markup("{\\emphasis x}"),
+            vec![Inline::Protected(vec![text("\\emphasis x")])]
+        );
+        assert_eq!(
+            parse_markup("\\textsc{Small} caps"),
+            vec![text("Small caps")]
+        );
+        assert_eq!(parse_markup("5$ and {"), vec![text("5$ and {")]);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod citation;
+pub mod date;
 pub mod entry;
+pub mod error;
+pub mod latex;
+pub mod markup;
 pub mod names;
@@ -3,55 +3,20 @@ use std::fmt::Debug;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case, take_until, take_while1},
-    character::complete::{char, multispace0, space0, space1},
+    character::complete::{char, multispace0},
     combinator::{recognize, verify},
-    multi::separated_list1,
-    sequence::{delimited, separated_pair, terminated},
+    multi::{many1, separated_list1},
+    sequence::{delimited, terminated},
     IResult,
 };
 
+use super::latex::{decode_latex, latex_special_char};
 use lazy_static::*;
+use parse_hyperlinks::take_until_unbalanced;
 use std::collections::BTreeSet;
+use unicode_segmentation::UnicodeSegmentation;
 
 lazy_static! {
-    static ref VON: BTreeSet<&'static str> = {
-        let mut m = BTreeSet::new();
-        m.insert("d'");
-        m.insert("da");
-        m.insert("das");
-        m.insert("de la");
-        m.insert("de las");
-        m.insert("de los");
-        m.insert("de");
-        m.insert("del");
-        m.insert("des");
-        m.insert("do");
-        m.insert("dos");
-        m.insert("du");
-        m.insert("van de");
-        m.insert("van der");
-        m.insert("van");
-        m.insert("d'");
-        m.insert("dei");
-        m.insert("del");
-        m.insert("della");
-        m.insert("delle");
-        m.insert("di");
-        m.insert("la");
-        m.insert("las");
-        m.insert("los");
-        m.insert("von der");
-        m.insert("von");
-        m.insert("zu");
-        m.insert("zum");
-        m.insert("zur");
-        m.insert("af");
-        m.insert("ze");
-        m.insert("Z");
-        m.insert("из");
-        m.insert("iz");
-        m
-    };
     static ref TITLE: BTreeSet<&'static str> = {
         let mut m = BTreeSet::new();
         m.insert("sir");
@@ -69,12 +34,40 @@ lazy_static! {
     };
 }
 
+/// articles that are skipped when sorting organisations, so "The World Bank" sorts under W
+pub(crate) const ARTICLES: &[&str] = &["the", "a", "an"];
+
+/// Letters that are written with two characters in a language, and so are initialised as a
+/// whole. English has none, so Thomas is just "T."
+fn digraphs(langid: &str) -> &'static [&'static str] {
+    match langid.to_lowercase().as_str() {
+        "czech" | "slovak" | "cs" | "sk" => &["Ch"],
+        "croatian" | "bosnian" | "serbian" | "hr" | "bs" | "sr" => &["Dž", "Lj", "Nj"],
+        "hungarian" | "magyar" | "hu" => &["Cs", "Dz", "Dzs", "Gy", "Ly", "Ny", "Sz", "Ty", "Zs"],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    /// a person, with a name made up of first, von, last and jr parts
+    #[default]
+    Person,
+    /// a name that is protected by braces as a whole, like `{World Health Organization}`,
+    /// which is kept as the last name and always printed as is
+    Literal,
+    /// the `others` in `and others`, meaning the list of names was truncated
+    Others,
+}
+
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct FullName<'a> {
     first: Vec<&'a str>,
     last: Vec<&'a str>,
     von: Vec<&'a str>,
+    jr: Vec<&'a str>,
     title: Vec<&'a str>,
+    kind: NameKind,
 }
 
 #[derive(Default, Clone, PartialEq, Eq)]
@@ -82,28 +75,86 @@ pub struct OwnedFullName {
     pub first: Vec<String>,
     pub last: Vec<String>,
     pub von: Vec<String>,
+    pub jr: Vec<String>,
     pub title: Vec<String>,
+    pub kind: NameKind,
+    /// the language of the entry the name is in, which determines how it is initialised
+    pub langid: Option<String>,
+}
+
+impl OwnedFullName {
+    /// The initials of the first names, e.g. ["J.-P.", "M."] for Jean-Paul Marie. Letters that
+    /// are written with two characters in the language of the name, like "Ch" in Czech, are
+    /// kept together.
+    pub fn initials(&self) -> Vec<String> {
+        let digraphs = self.langid.as_deref().map_or(&[][..], digraphs);
+        self.first
+            .iter()
+            .map(|name| {
+                name.split('-')
+                    .filter_map(|part| {
+                        let letter = digraphs
+                            .iter()
+                            .filter(|d| part.starts_with(*d))
+                            .max_by_key(|d| d.len())
+                            .copied()
+                            .or_else(|| part.graphemes(true).next())?;
+                        Some(format!("{}.", letter))
+                    })
+                    .collect::<Vec<String>>()
+                    .join("-")
+            })
+            .collect()
+    }
+
+    /// The key to sort names by: the last name followed by the first names, or for
+    /// organisations their name from the first significant word on.
+    pub fn sort_key(&self) -> String {
+        let key = match self.kind {
+            NameKind::Literal => {
+                let literal = self.last.join(" ");
+                let mut words: Vec<&str> = literal.split_whitespace().collect();
+                if words.len() > 1 && ARTICLES.contains(&words[0].to_lowercase().as_str()) {
+                    words.remove(0);
+                }
+                words.join(" ")
+            }
+            NameKind::Others => String::new(),
+            NameKind::Person => format!(
+                "{} {} {}",
+                self.last.join(" "),
+                self.first.join(" "),
+                self.jr.join(" ")
+            ),
+        };
+        key.trim().to_lowercase()
+    }
 }
 impl Debug for OwnedFullName {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "First({}) Von({}) Last({}) Title({})",
+            "First({}) Von({}) Last({}) Jr({}) Title({})",
             &self.first.join(" "),
             &self.von.join(" "),
             &self.last.join(" "),
+            &self.jr.join(" "),
             &self.title.join(" "),
         )
     }
 }
 
+/// the name parts are converted to unicode when they are owned
 impl<'a> From<FullName<'a>> for OwnedFullName {
     fn from(value: FullName) -> Self {
         Self {
-            first: value.first.into_iter().map(|v| v.to_string()).collect(),
-            last: value.last.into_iter().map(|v| v.to_string()).collect(),
-            von: value.von.into_iter().map(|v| v.to_string()).collect(),
-            title: value.title.into_iter().map(|v| v.to_string()).collect(),
+            first: value.first.into_iter().map(decode_latex).collect(),
+            last: value.last.into_iter().map(decode_latex).collect(),
+            von: value.von.into_iter().map(decode_latex).collect(),
+            jr: value.jr.into_iter().map(decode_latex).collect(),
+            title: value.title.into_iter().map(decode_latex).collect(),
+            kind: value.kind,
+            langid: None,
         }
     }
 }
@@ -112,131 +163,214 @@ impl<'a> Debug for FullName<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "First({}) Von({}) Last({}) Title({})",
+            "First({}) Von({}) Last({}) Jr({}) Title({})",
             &self.first.join(" "),
             &self.von.join(" "),
             &self.last.join(" "),
+            &self.jr.join(" "),
             &self.title.join(" "),
         )
     }
 }
 
+impl<'a> FullName<'a> {
+    /// Move titles like "Sir" or "Dr" out of the first and last name. BibTeX has no notion of
+    /// titles so this is not done by default.
+    pub fn with_titles(self) -> Self {
+        let (mut title, first): (Vec<&str>, Vec<&str>) =
+            self.first.into_iter().partition(|w| TITLE.contains(w));
+        let (title_last, last): (Vec<&str>, Vec<&str>) =
+            self.last.into_iter().partition(|w| TITLE.contains(w));
+        title.extend(title_last);
+        Self {
+            first,
+            last,
+            title,
+            ..self
+        }
+    }
+}
+
 fn hyphenated_word(input: &str) -> IResult<&str, &str> {
     recognize(separated_list1(tag("-"), inner_word))(input)
 }
 
+/// A word without spaces, like `O'Neil`, `Doe2` or `G{\"o}del`. Hyphens and periods are left
+/// out, since they join parts of a word and end initials.
 fn inner_word(input: &str) -> IResult<&str, &str> {
-    verify(take_while1(|c: char| c.is_alphabetic()), |w: &str| {
-        w.to_lowercase() != "and"
-    })(input)
+    verify(
+        recognize(many1(alt((
+            take_while1(|c: char| !c.is_whitespace() && !"{}\\\"~,-.".contains(c)),
+            latex_special_char,
+        )))),
+        |w: &str| w.to_lowercase() != "and",
+    )(input)
 }
+
+/// words are kept with their braces or quotes, which matter for splitting the name
+/// into its parts. See [`unprotected`]
 fn word(input: &str) -> IResult<&str, &str> {
+    // words may start with a special character like `{\"O}`,
+    // so those have to be tried before brace quoted literals
     let (tail, word) = alt((
-        brace_quoted_literal,
-        quote_quoted_literal,
         hyphenated_word,
         inner_word,
+        recognize(brace_quoted_literal),
+        recognize(quote_quoted_literal),
     ))(input)?;
     Ok((tail, word))
 }
 
+/// An initial like `J.`, which is kept without its period. Hyphenated names may be partly or
+/// wholly initialised, like `J.-P.` or `J.-Paul`, and are kept together.
 fn initial(input: &str) -> IResult<&str, &str> {
-    let (tail, word) = terminated(word, tag("."))(input)?;
-    Ok((tail, word))
+    let (tail, word) = recognize(separated_list1(
+        tag("-"),
+        alt((recognize(terminated(word, tag("."))), word)),
+    ))(input)?;
+    Ok((tail, word.strip_suffix('.').unwrap_or(word)))
+}
+
+/// words in names can be separated by whitespace, including line breaks, or ties (`~`)
+fn name_space(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c == '~' || c.is_whitespace())(input)
 }
 
 fn space_seperated_words(input: &str) -> IResult<&str, Vec<&str>> {
-    let (tail, words) = separated_list1(space1, alt((initial, word)))(input)?;
+    let (tail, words) = separated_list1(name_space, alt((initial, word)))(input)?;
     Ok((tail, words))
 }
 
 fn brace_quoted_literal(input: &str) -> IResult<&str, &str> {
-    delimited(char('{'), take_until("}"), char('}'))(input)
+    delimited(char('{'), take_until_unbalanced('{', '}'), char('}'))(input)
 }
 fn quote_quoted_literal(input: &str) -> IResult<&str, &str> {
     delimited(tag("\""), take_until("\""), tag("\""))(input)
 }
 
-fn last_first(input: &str) -> IResult<&str, FullName, nom::error::Error<&str>> {
-    let (tail, (last_with_von, first_with_von)) = separated_pair(
-        space_seperated_words,
-        delimited(multispace0, tag(","), multispace0),
-        space_seperated_words,
-    )(input)?;
-    let (mut von, first): (Vec<&str>, Vec<&str>) =
-        first_with_von.iter().partition(|&w| VON.contains(w));
-    let (last_von, last): (Vec<&str>, Vec<&str>) =
-        last_with_von.iter().partition(|&w| VON.contains(w));
-    von.extend(last_von);
-    let (mut title, first): (Vec<&str>, Vec<&str>) = first.iter().partition(|&w| TITLE.contains(w));
-    let (title_last, last): (Vec<&str>, Vec<&str>) = last.iter().partition(|&w| TITLE.contains(w));
-    title.extend(title_last);
-    Ok((
-        tail,
-        FullName {
-            last,
-            first,
-            title,
-            von,
-        },
-    ))
+/// the word without the braces or quotes around it, if it is protected as a whole
+fn unprotected(word: &str) -> &str {
+    match alt((brace_quoted_literal, quote_quoted_literal))(word) {
+        // `{\AA}` is a special character, not a protected word
+        Ok(("", inner)) if !inner.starts_with('\\') => inner,
+        _ => word,
+    }
 }
 
-fn first_last(input: &str) -> IResult<&str, FullName, nom::error::Error<&str>> {
-    let (tail, mut words) = space_seperated_words(input)?;
-
-    let first_von = words.iter().position(|w| VON.contains(w));
-    let last_von = words.iter().rev().position(|w| VON.contains(w));
-    let (first, von, last) = match first_von {
-        Some(i) => {
-            // not the prettiest code I've ever written I'll admit,
-            // but hey, it works
-            let num_words = words.len();
-            let mut first = vec![];
-            let mut von = vec![];
-            let mut last = vec![];
+/// Whether a word belongs in the von part, which BibTeX decides by the case of its first
+/// letter. Only letters outside of braces count, apart from special characters like `{\"o}`,
+/// so `{de la} Cruz` has no von part while `{\"u}ber Mann` does.
+fn is_lowercase_word(word: &str) -> bool {
+    let mut rest = word;
+    while let Some(c) = rest.chars().next() {
+        if c.is_alphabetic() {
+            return c.is_lowercase();
+        }
+        if c == '{' || c == '\\' {
+            if let Ok((tail, special)) = latex_special_char(rest) {
+                match decode_latex(special).chars().find(|c| c.is_alphabetic()) {
+                    Some(letter) => return letter.is_lowercase(),
+                    None => {
+                        rest = tail;
+                        continue;
+                    }
+                }
+            }
+        }
+        match c {
+            '{' => match brace_quoted_literal(rest) {
+                Ok((tail, _)) => rest = tail,
+                Err(_) => return false,
+            },
+            // quoted words are protected as a whole
+            '"' => return false,
+            _ => rest = &rest[c.len_utf8()..],
+        }
+    }
+    false
+}
 
-            // if first will be found, so will last.
-            let j = num_words - last_von.unwrap() - 1;
+/// Split the words before the first comma into the von and last part. The von part runs up to
+/// the last lower case word, but the last word is always part of the last name.
+fn split_von_last(mut words: Vec<&str>) -> (Vec<&str>, Vec<&str>) {
+    let von_end = words[..words.len() - 1]
+        .iter()
+        .rposition(|w| is_lowercase_word(w))
+        .map_or(0, |i| i + 1);
+    let last = words.split_off(von_end);
+    (words, last)
+}
 
-            for _ in 0..i {
-                first.push(words.remove(0))
+/// Split the comma separated parts of a name following the BibTeX rules, for any of
+/// "First von Last", "von Last, First" or "von Last, Jr, First"
+fn split_name<'a>(mut parts: Vec<Vec<&'a str>>) -> FullName<'a> {
+    if let [words] = parts.as_slice() {
+        if let [word] = words.as_slice() {
+            if *word == "others" {
+                return FullName {
+                    kind: NameKind::Others,
+                    ..Default::default()
+                };
             }
-            for _ in i..=j {
-                von.push(words.remove(0))
+            let literal = unprotected(word);
+            if literal.len() != word.len() {
+                return FullName {
+                    last: vec![literal],
+                    kind: NameKind::Literal,
+                    ..Default::default()
+                };
             }
-            for _ in j..num_words - 1 {
-                last.push(words.remove(0))
-            }
-            (first, von, last)
         }
-        None => {
-            let last = vec![words.remove(&words.len() - 1)];
-            let first = words;
-            let von = vec![];
-            (first, von, last)
+    }
+    let mut words = parts.remove(0);
+    let (first, jr) = match parts.len() {
+        0 => {
+            // the von part starts at the first lower case word, and if there is none
+            // only the last word is the last name
+            let von_start = words[..words.len() - 1]
+                .iter()
+                .position(|w| is_lowercase_word(w))
+                .unwrap_or(words.len() - 1);
+            let rest = words.split_off(von_start);
+            (std::mem::replace(&mut words, rest), vec![])
+        }
+        1 => (parts.remove(0), vec![]),
+        _ => {
+            let jr = parts.remove(0);
+            (parts.remove(0), jr)
         }
     };
+    let (von, last) = split_von_last(words);
+    let strip =
+        |words: Vec<&'a str>| -> Vec<&'a str> { words.into_iter().map(unprotected).collect() };
+    FullName {
+        first: strip(first),
+        last: strip(last),
+        von: strip(von),
+        jr: strip(jr),
+        title: vec![],
+        kind: NameKind::Person,
+    }
+}
 
-    let (mut title, first): (Vec<&str>, Vec<&str>) = first.iter().partition(|&w| TITLE.contains(w));
-    let (title_last, last): (Vec<&str>, Vec<&str>) = last.iter().partition(|&w| TITLE.contains(w));
-    title.extend(title_last);
-
-    Ok((
-        tail,
-        FullName {
-            first,
-            last,
-            von,
-            title,
-        },
-    ))
+fn name(input: &str) -> IResult<&str, FullName<'_>, nom::error::Error<&str>> {
+    let (tail, parts) = verify(
+        separated_list1(
+            delimited(multispace0, tag(","), multispace0),
+            space_seperated_words,
+        ),
+        // BibTeX names have at most two commas
+        |parts: &Vec<Vec<&str>>| parts.len() <= 3,
+    )(input)?;
+    Ok((tail, split_name(parts)))
 }
 
-pub fn and_seperated_names(input: &str) -> IResult<&str, Vec<FullName>, nom::error::Error<&str>> {
+pub fn and_seperated_names(
+    input: &str,
+) -> IResult<&str, Vec<FullName<'_>>, nom::error::Error<&str>> {
     let (tail, names) = separated_list1(
-        delimited(space0, tag_no_case("and"), space0),
-        alt((last_first, first_last)),
+        delimited(multispace0, tag_no_case("and"), multispace0),
+        name,
     )(input)?;
 
     Ok((tail, names))
@@ -248,6 +382,11 @@ mod test {
     use super::*;
     use anyhow::Result;
 
+    fn name_with_titles(input: &str) -> IResult<&str, FullName<'_>> {
+        let (tail, name) = name(input)?;
+        Ok((tail, name.with_titles()))
+    }
+
     macro_rules! parse_assert {
         ($func:ident, $test:expr, $expected:expr) => {
             let (tail, ans) = $func($test)?;
@@ -268,115 +407,362 @@ mod test {
 
     parse_test!(
         test_last_first,
-        last_first,
+        name,
         "Newton, Isaac",
         FullName {
             first: vec!["Isaac"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Newton"]
         }
     );
+    parse_test!(
+        test_apostrophe,
+        name,
+        "Jean le Rond d'Alembert",
+        FullName {
+            first: vec!["Jean"],
+            title: vec![],
+            von: vec!["le"],
+            jr: vec![],
+            kind: NameKind::Person,
+            last: vec!["Rond", "d'Alembert"]
+        }
+    );
+    parse_test!(
+        test_punctuation_and_digits,
+        name,
+        "O'Neil-Smith, Jane2",
+        FullName {
+            first: vec!["Jane2"],
+            title: vec![],
+            von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
+            last: vec!["O'Neil-Smith"]
+        }
+    );
+    parse_test!(
+        test_hyphenated_initials,
+        name,
+        "Doe, J.-P.",
+        FullName {
+            first: vec!["J.-P"],
+            title: vec![],
+            von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
+            last: vec!["Doe"]
+        }
+    );
     parse_test!(
         test_last_last_first,
-        last_first,
+        name,
         "Brinch Hansen, Per",
         FullName {
             first: vec!["Per"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Brinch", "Hansen"]
         }
     );
 
     parse_test!(
         test_last_first_first,
-        last_first,
+        name,
         "Jackson, Michael Joseph",
         FullName {
             first: vec!["Michael", "Joseph"],
             last: vec!["Jackson"],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_last_first_init,
-        last_first,
+        name,
         "Jackson, Michael J",
         FullName {
             first: vec!["Michael", "J"],
             last: vec!["Jackson"],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_last_init_init,
-        last_first,
+        name,
         "Jackson, M J",
         FullName {
             first: vec!["M", "J"],
             last: vec!["Jackson"],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_first_last,
-        first_last,
+        name,
         "Isaac Newton",
         FullName {
             first: vec!["Isaac"],
             last: vec!["Newton"],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_first_first_last,
-        first_last,
+        name,
         "Michael Joseph Jackson",
         FullName {
             first: vec!["Michael", "Joseph"],
             last: vec!["Jackson"],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_von_last_initial,
-        last_first,
+        name,
         "van      Beethoven ,    L",
         FullName {
             first: vec!["L"],
             last: vec!["Beethoven"],
             von: vec!["van"],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_von_last_first,
-        last_first,
+        name,
         "van Beethoven, Ludwig",
         FullName {
             first: vec!["Ludwig"],
             last: vec!["Beethoven"],
             von: vec!["van"],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
     parse_test!(
         test_first_von_last,
-        first_last,
+        name,
         "Ludwig van Beethoven",
         FullName {
             first: vec!["Ludwig"],
             last: vec!["Beethoven"],
             von: vec!["van"],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
         }
     );
+    #[test]
+    fn test_bibtex_name_parts() -> Result<()> {
+        for (test, expected) in vec![
+            (
+                "Doe, Jr., John",
+                FullName {
+                    first: vec!["John"],
+                    last: vec!["Doe"],
+                    von: vec![],
+                    jr: vec!["Jr"],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "de la Fontaine, Jr., Jean",
+                FullName {
+                    first: vec!["Jean"],
+                    last: vec!["Fontaine"],
+                    von: vec!["de", "la"],
+                    jr: vec!["Jr"],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "Jean de La Fontaine",
+                FullName {
+                    first: vec!["Jean"],
+                    last: vec!["La", "Fontaine"],
+                    von: vec!["de"],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "Vincent Van Gogh",
+                FullName {
+                    first: vec!["Vincent", "Van"],
+                    last: vec!["Gogh"],
+                    von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "Jean {de la} Fontaine",
+                FullName {
+                    first: vec!["Jean", "de la"],
+                    last: vec!["Fontaine"],
+                    von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "Hans {\\\"u}ber Mann",
+                FullName {
+                    first: vec!["Hans"],
+                    last: vec!["Mann"],
+                    von: vec!["{\\\"u}ber"],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "{\\'E}mile Zola",
+                FullName {
+                    first: vec!["{\\'E}mile"],
+                    last: vec!["Zola"],
+                    von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "von Neumann",
+                FullName {
+                    first: vec![],
+                    last: vec!["Neumann"],
+                    von: vec!["von"],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+            (
+                "jones",
+                FullName {
+                    first: vec![],
+                    last: vec!["jones"],
+                    von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
+                    title: vec![],
+                },
+            ),
+        ] {
+            let (tail, name) = name(test)?;
+            assert_eq!(tail, "", "{}", test);
+            assert_eq!(name, expected, "{}", test);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_names() -> Result<()> {
+        let (tail, names) =
+            and_seperated_names("{World Health Organization} and {The World Bank} and Jane Doe")?;
+        assert_eq!(tail, "");
+        let names: Vec<OwnedFullName> = names.into_iter().map(|n| n.into()).collect();
+        assert_eq!(names[0].kind, NameKind::Literal);
+        assert_eq!(names[0].last, vec!["World Health Organization"]);
+        assert!(names[0].first.is_empty());
+        assert_eq!(names[1].kind, NameKind::Literal);
+        assert_eq!(names[2].kind, NameKind::Person);
+        assert_eq!(names[0].sort_key(), "world health organization");
+        assert_eq!(names[1].sort_key(), "world bank");
+        assert_eq!(names[2].sort_key(), "doe jane");
+
+        let (tail, names) = and_seperated_names(r#"{{\"O}sterreichische Nationalbank}"#)?;
+        assert_eq!(tail, "");
+        let name: OwnedFullName = names[0].clone().into();
+        assert_eq!(name.kind, NameKind::Literal);
+        assert_eq!(name.last, vec!["Österreichische Nationalbank"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_and_others() -> Result<()> {
+        let (tail, names) = and_seperated_names("Jane Doe and others")?;
+        assert_eq!(tail, "");
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[1].kind, NameKind::Others);
+        let owned: OwnedFullName = names[1].clone().into();
+        assert_eq!(owned.sort_key(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_initials() -> Result<()> {
+        for (test, langid, expected) in [
+            ("Sartre, Jean-Paul Charles", None, vec!["J.-P.", "C."]),
+            ("Sartre, J.-P. C.", None, vec!["J.-P.", "C."]),
+            ("Sartre, J.-Paul", None, vec!["J.-P."]),
+            ("Gombrowicz, Witold", Some("polish"), vec!["W."]),
+            ("Hobbes, Thomas", Some("english"), vec!["T."]),
+            ("Chalupa, Christian", None, vec!["C."]),
+            ("Chalupa, Christian", Some("czech"), vec!["Ch."]),
+            ("Nagy, Gyula Zsolt", Some("hungarian"), vec!["Gy.", "Zs."]),
+            ("Horvat, Ljubo", Some("croatian"), vec!["Lj."]),
+            ("{\\\"O}zil, {\\'E}mile", None, vec!["É."]),
+        ] {
+            let (_, name) = name(test)?;
+            let name = OwnedFullName {
+                langid: langid.map(String::from),
+                ..name.into()
+            };
+            assert_eq!(name.initials(), expected, "{}", test);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_too_many_commas() {
+        assert!(name("Doe, Jr., John, Smith").is_err());
+    }
+
+    #[test]
+    fn test_latex_names() -> Result<()> {
+        let (tail, names) = and_seperated_names(
+            "Schr{\\\"o}dinger, Erwin and Fran\\c{c}ois~Vi\\`{e}te and {\\AA}str{\\\"o}m, K.",
+        )?;
+        assert_eq!(tail, "");
+        let names: Vec<OwnedFullName> = names.into_iter().map(|n| n.into()).collect();
+        assert_eq!(names[0].last, vec!["Schrödinger"]);
+        assert_eq!(names[0].first, vec!["Erwin"]);
+        assert_eq!(names[1].first, vec!["François"]);
+        assert_eq!(names[1].last, vec!["Viète"]);
+        assert_eq!(names[2].last, vec!["Åström"]);
+        Ok(())
+    }
+
     #[test]
     fn test_quoted_literal() -> Result<()> {
         for (test, answer) in vec![
@@ -400,46 +786,54 @@ mod test {
 
     parse_test!(
         test_first_init_last,
-        first_last,
+        name,
         "Donald E. Knuth",
         FullName {
             first: vec!["Donald", "E"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Knuth"]
         }
     );
     parse_test!(
         test_quoted_first_last,
-        first_last,
+        name,
         "Ronald {Van der Jawel}",
         FullName {
             first: vec!["Ronald"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Van der Jawel"]
         }
     );
 
     parse_test!(
         test_and_component_last_first,
-        last_first,
+        name,
         "Fisher, James",
         FullName {
             first: vec!["James"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Fisher"]
         }
     );
     parse_test!(
         test_and_component_first_last,
-        first_last,
+        name,
         "John Clark",
         FullName {
             first: vec!["John"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Clark"]
         }
     );
@@ -452,12 +846,16 @@ mod test {
                 first: vec!["James"],
                 title: vec![],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
                 last: vec!["Fisher"]
             },
             FullName {
                 first: vec!["John"],
                 title: vec![],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
                 last: vec!["Clark"]
             }
         ]
@@ -472,29 +870,39 @@ mod test {
                 first: vec!["Frank"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
                 last: vec!["Mittelbach"]
             },
             FullName {
                 first: vec!["Michel"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
                 last: vec!["Gossens"]
             },
             FullName {
                 first: vec!["Johannes"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
                 last: vec!["Braams"]
             },
             FullName {
                 first: vec!["David"],
             title: vec![],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
                 last: vec!["Carlisle"]
             },
             FullName {
                 first: vec!["Chris"],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
             title: vec![],
                 last: vec!["Rowley"]
             }
@@ -509,18 +917,24 @@ mod test {
                 first: vec!["Geert"],
                 title: vec![],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
                 last: vec!["Van der Plas"]
             },
             FullName {
                 first: vec!["John"],
                 title: vec![],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
                 last: vec!["Doe"]
             },
             FullName {
                 first: vec![],
                 title: vec![],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Literal,
                 last: vec!["Barnes and Noble"]
             },
         ]
@@ -528,12 +942,14 @@ mod test {
 
     parse_test!(
         test_many_name_components,
-        first_last,
+        name_with_titles,
         "Charles Louis Xavier Joseph de la Vallee Poussin III",
         FullName {
             first: vec!["Charles", "Louis", "Xavier", "Joseph"],
             title: vec!["III"],
             von: vec!["de", "la"],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Vallee", "Poussin"]
         }
     );
@@ -543,12 +959,14 @@ mod test {
             first: vec!["Charles", "Louis", "Xavier", "Joseph"],
             title: vec!["III"],
             von: vec!["de", "la"],
+            jr: vec![],
+            kind: NameKind::Person,
             last: vec!["Vallee", "Poussin"],
         };
 
         assert_eq!(
             format!("{:?}", name),
-            "First(Charles Louis Xavier Joseph) Von(de la) Last(Vallee Poussin) Title(III)"
+            "First(Charles Louis Xavier Joseph) Von(de la) Last(Vallee Poussin) Jr() Title(III)"
         );
 
         Ok(())
@@ -564,12 +982,15 @@ mod test {
             ],
             title: vec!["III".to_string()],
             von: vec!["de".to_string(), "la".to_string()],
+            jr: vec![],
+            kind: NameKind::Person,
+            langid: None,
             last: vec!["Vallee".to_string(), "Poussin".to_string()],
         };
 
         assert_eq!(
             format!("{:?}", name),
-            "First(Charles Louis Xavier Joseph) Von(de la) Last(Vallee Poussin) Title(III)"
+            "First(Charles Louis Xavier Joseph) Von(de la) Last(Vallee Poussin) Jr() Title(III)"
         );
 
         Ok(())
@@ -586,6 +1007,8 @@ mod test {
                     first: vec!["Albert"],
                     last: vec!["Einstein"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -595,6 +1018,8 @@ mod test {
                     first: vec!["Emmet"],
                     last: vec!["Brown"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec!["Dr"],
                 },
             ),
@@ -604,6 +1029,8 @@ mod test {
                     first: vec!["Leonardo"],
                     last: vec!["Vinci"],
                     von: vec!["da"],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -613,6 +1040,8 @@ mod test {
                     first: vec!["Arthur"],
                     last: vec!["Conan", "Doyle"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec!["Sir"],
                 },
             ),
@@ -622,6 +1051,8 @@ mod test {
                     first: vec!["Marie"],
                     last: vec!["Curie"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec!["Madame"],
                 },
             ),
@@ -631,6 +1062,8 @@ mod test {
                     first: vec!["Jean-Jacques"],
                     last: vec!["Rousseau"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -640,6 +1073,8 @@ mod test {
                     first: vec!["Friedrich"],
                     last: vec!["Nietzsche"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -649,6 +1084,8 @@ mod test {
                     first: vec!["Ada"],
                     last: vec!["Lovelace"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -658,6 +1095,8 @@ mod test {
                     first: vec!["Vincent"],
                     last: vec!["Gogh"],
                     von: vec!["van"],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -667,6 +1106,8 @@ mod test {
                     first: vec!["Amelia"],
                     last: vec!["Earhart"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -676,6 +1117,8 @@ mod test {
                     first: vec!["Hermann"],
                     last: vec!["Hesse"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -685,6 +1128,8 @@ mod test {
                     first: vec!["Karl"],
                     last: vec!["Marx"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -694,6 +1139,8 @@ mod test {
                     first: vec!["Che"],
                     last: vec!["Guevara"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -703,6 +1150,8 @@ mod test {
                     first: vec!["Sigmund"],
                     last: vec!["Freud"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -712,6 +1161,8 @@ mod test {
                     first: vec!["Virginia"],
                     last: vec!["Woolf"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -721,6 +1172,8 @@ mod test {
                     first: vec!["Vasco"],
                     last: vec!["Gama"],
                     von: vec!["da"],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -730,6 +1183,8 @@ mod test {
                     first: vec!["Catherine"],
                     last: vec!["Medici"],
                     von: vec!["de"],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -739,6 +1194,8 @@ mod test {
                     first: vec!["Francisco"],
                     last: vec!["Goya"],
                     von: vec!["de"],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -748,6 +1205,8 @@ mod test {
                     first: vec!["William"],
                     last: vec!["Shakespeare"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -757,6 +1216,8 @@ mod test {
                     first: vec!["Niccolo"],
                     last: vec!["Machiavelli"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -766,6 +1227,8 @@ mod test {
                     first: vec!["Dante"],
                     last: vec!["Alighieri"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -775,6 +1238,8 @@ mod test {
                     first: vec!["Gregor"],
                     last: vec!["Mendel"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -784,6 +1249,8 @@ mod test {
                     first: vec!["Emily"],
                     last: vec!["Dickinson"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -793,6 +1260,8 @@ mod test {
                     first: vec!["Jules"],
                     last: vec!["Verne"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -802,6 +1271,8 @@ mod test {
                     first: vec!["Edgar", "Allan"],
                     last: vec!["Poe"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -811,6 +1282,8 @@ mod test {
                     first: vec!["Simón"],
                     last: vec!["Bolívar"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -820,6 +1293,8 @@ mod test {
                     first: vec!["Søren"],
                     last: vec!["Kierkegaard"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -829,6 +1304,8 @@ mod test {
                     first: vec!["Fyodor"],
                     last: vec!["Dostoevsky"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -838,6 +1315,8 @@ mod test {
                     first: vec!["Mikhail"],
                     last: vec!["Lomonosov"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -847,6 +1326,8 @@ mod test {
                     first: vec!["Aung", "San"],
                     last: vec!["Suu", "Kyii"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -856,6 +1337,8 @@ mod test {
                     first: vec!["Nguyễn"],
                     last: vec!["Du"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -865,6 +1348,8 @@ mod test {
                     first: vec!["Sun"],
                     last: vec!["Yat-sen"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -874,6 +1359,8 @@ mod test {
                     first: vec!["Hugo"],
                     last: vec!["Chávez"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -883,6 +1370,8 @@ mod test {
                     first: vec!["Frida"],
                     last: vec!["Kahlo"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -892,6 +1381,8 @@ mod test {
                     first: vec!["Salvador"],
                     last: vec!["Allende"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -901,6 +1392,8 @@ mod test {
                     first: vec!["Gabriel"],
                     last: vec!["Garcia", "Márquez"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -910,6 +1403,8 @@ mod test {
                     first: vec!["Antoni"],
                     last: vec!["Gaudí"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -919,6 +1414,8 @@ mod test {
                     first: vec!["Johan", "Sebastian"],
                     last: vec!["Bach"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -928,6 +1425,8 @@ mod test {
                     first: vec!["Blaise"],
                     last: vec!["Pascal"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -937,6 +1436,8 @@ mod test {
                     first: vec!["René"],
                     last: vec!["Descartes"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -946,6 +1447,8 @@ mod test {
                     first: vec!["Mahatma"],
                     last: vec!["Gandhi"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -955,6 +1458,8 @@ mod test {
                     first: vec!["Niels"],
                     last: vec!["Bohr"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -964,6 +1469,8 @@ mod test {
                     first: vec!["Léon"],
                     last: vec!["Blum"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -973,6 +1480,8 @@ mod test {
                     first: vec!["Jacques"],
                     last: vec!["Chirac"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -982,6 +1491,8 @@ mod test {
                     first: vec!["Václav"],
                     last: vec!["Havel"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -991,6 +1502,8 @@ mod test {
                     first: vec!["Jorge", "Luis"],
                     last: vec!["Borges"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1000,6 +1513,8 @@ mod test {
                     first: vec!["Paulo"],
                     last: vec!["Coelho"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1009,6 +1524,8 @@ mod test {
                     first: vec!["José"],
                     last: vec!["Saramago"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1018,6 +1535,8 @@ mod test {
                     first: vec!["Arundhati"],
                     last: vec!["Roy"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1027,6 +1546,8 @@ mod test {
                     first: vec!["Haruki"],
                     last: vec!["Murakami"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1036,6 +1557,8 @@ mod test {
                     first: vec!["Kenzaburō"],
                     last: vec!["Ōe"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1045,11 +1568,14 @@ mod test {
                     last: vec!["Naguib"],
                     first: vec!["Mahfouz"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
         ] {
-            let (tail, name) = last_first(test)?;
+            let (tail, name) = name(test)?;
+            let name = name.with_titles();
             assert_eq!(tail, "", "{}", &test);
             assert_eq!(name, expected, "{}", &test);
         }
@@ -1071,6 +1597,8 @@ mod test {
                     first: vec!["Лев"],
                     last: vec!["Толстой"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1080,6 +1608,8 @@ mod test {
                     first: vec![],
                     last: vec!["Αριστοτέλης"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1089,6 +1619,8 @@ mod test {
                     first: vec![],
                     last: vec!["孔子"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1098,6 +1630,8 @@ mod test {
                     first: vec!["אברהם"],
                     last: vec!["לינקולן"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1107,6 +1641,8 @@ mod test {
                     first: vec!["محمد"],
                     last: vec!["علي"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1116,6 +1652,8 @@ mod test {
                     first: vec!["由紀夫"],
                     last: vec!["三島"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1125,6 +1663,8 @@ mod test {
                     first: vec!["정은"],
                     last: vec!["김"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1134,6 +1674,8 @@ mod test {
                     first: vec!["Тарас"],
                     last: vec!["Шевченко"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1143,6 +1685,8 @@ mod test {
                     first: vec!["ابن"],
                     last: vec!["سينا"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1152,6 +1696,8 @@ mod test {
                     first: vec!["書豪"],
                     last: vec!["林"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1161,6 +1707,8 @@ mod test {
                     first: vec!["정환"],
                     last: vec!["김"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1170,6 +1718,8 @@ mod test {
                     last: vec!["太郎"],
                     first: vec!["山田"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
@@ -1179,11 +1729,13 @@ mod test {
                     first: vec!["Владимир"],
                     last: vec!["Путин"],
                     von: vec![],
+                    jr: vec![],
+                    kind: NameKind::Person,
                     title: vec![],
                 },
             ),
         ] {
-            let (tail, name) = first_last(test)?;
+            let (tail, name) = name(test)?;
             assert_eq!(tail, "", "{}", test);
             assert_eq!(name, expected, "{}", test);
         }
@@ -2,12 +2,16 @@ use std::collections::BTreeMap;
 
 use crate::{
     formaters::Formatter,
-    parsing::names::{and_seperated_names, OwnedFullName},
+    parsing::citation::CitationMode,
+    parsing::date::{Date, EntryDate},
+    parsing::entry::NameLists,
+    parsing::names::{NameKind, OwnedFullName},
 };
-use chrono::NaiveDate;
-use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Stylizer, ThesisKind};
+use super::{
+    case::Case, cited_names, editor_label, field, jr, names_of, surname, title, truncate_others,
+    with_notes, Cited, Stylizer, ThesisKind,
+};
 
 #[derive(Default)]
 pub struct ApaStylizer<T: Formatter> {
@@ -17,17 +21,16 @@ pub struct ApaStylizer<T: Formatter> {
 impl<T: Formatter> Stylizer for ApaStylizer<T> {
     fn fmt_unpublished(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         self.fmt.italics(&mut title);
         out.push_str(&title);
@@ -38,331 +41,538 @@ impl<T: Formatter> Stylizer for ApaStylizer<T> {
 
     fn fmt_techreport(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
         self.fmt.italics(&mut title);
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let number = fields.get("number").unwrap();
-        let institution = fields.get("institution").unwrap();
-        let address = fields.get("address").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let date = EntryDate::from_fields(fields);
+        // biblatex `@report`s say what kind of report they are in their `type` field
+        let mut report = fields
+            .get("type")
+            .map(|t| self.fmt.markup(t))
+            .unwrap_or("tech. rep.".to_string());
+        if let Some(number) = fields.get("number") {
+            report.push_str(&format!(" No. {}", number));
+        }
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(&title);
-        out.push(' ');
-        out.push_str(&format!("(tech. rep. No. {}). ", number));
-        out.push_str(&format!("{}. ", institution));
-        out.push_str(&format!("{}.", address));
+        out.push_str(&format!(" ({}).", report));
+        push_sentences(
+            &mut out,
+            &[
+                field(&self.fmt, fields, "institution"),
+                field(&self.fmt, fields, "address"),
+            ],
+        );
 
         out
     }
 
-    fn fmt_proceedings(&self, fields: BTreeMap<String, String>) -> String {
+    fn fmt_proceedings(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
         self.fmt.italics(&mut title);
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let editors_str = fields.get("editor").unwrap();
-        let (_tail, edrs) = and_seperated_names(editors_str).unwrap();
-        let editor_names: Vec<OwnedFullName> = edrs.into_iter().map(|n| n.into()).collect();
-        let volume = fields.get("volume").unwrap();
-        let publisher = fields.get("publisher").unwrap();
-        out.push_str(&Self::fmt_authors(self, editor_names));
-        out.push_str(" (Eds.). ");
+        let date = EntryDate::from_fields(fields);
+        let editor_names = names_of(names, "editor");
+        let volume = fields.get("volume");
+        let publisher = field(&self.fmt, fields, "publisher");
+        out.push_str(&self.fmt_editor_element(&editor_names));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(&title);
-        out.push_str(&format!(" (Vol. {}). ", volume));
-        out.push_str(publisher);
+        if let Some(v) = volume {
+            out.push_str(&format!(" (Vol. {})", v));
+        }
         out.push('.');
+        push_sentences(&mut out, &[publisher]);
         out
     }
 
     fn fmt_thesis(
         &self,
         kind: ThesisKind,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
         self.fmt.italics(&mut title);
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let school = fields.get("school").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let date = EntryDate::from_fields(fields);
+        let school = fields
+            .get("school")
+            .or(fields.get("institution"))
+            .map(|s| format!(", {}", self.fmt.markup(s)))
+            .unwrap_or_default();
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(&title);
         out.push(' ');
         match kind {
-            ThesisKind::Phd => out.push_str(&format!("[Doctoral dissertation, {}].", school)),
-            ThesisKind::Msc => out.push_str(&format!("[Master's thesis, {}].", school)),
+            ThesisKind::Phd => out.push_str(&format!("[Doctoral dissertation{}].", school)),
+            ThesisKind::Msc => out.push_str(&format!("[Master's thesis{}].", school)),
+            ThesisKind::Other(k) => out.push_str(&format!("[{}{}].", self.fmt.markup(&k), school)),
         };
         out
     }
 
-    fn fmt_misc(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_misc(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push_str(". ");
+        let title = &title(&self.fmt, fields, Case::Sentence);
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(title);
         if let Some(n) = fields.get("note") {
-            out.push_str(&format!(" [{}]", n))
+            out.push_str(&format!(" [{}]", self.fmt.markup(n)))
         }
         out.push('.');
 
         out
     }
 
-    fn fmt_manual(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_manual(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
         self.fmt.italics(&mut title);
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let organization = fields.get("organization").unwrap();
-        let address = fields.get("address").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push_str(". ");
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(&title);
-        out.push_str(". ");
-        out.push_str(&format!("{}. ", organization));
-        out.push_str(&format!("{}.", address));
+        out.push('.');
+        push_sentences(
+            &mut out,
+            &[
+                field(&self.fmt, fields, "organization"),
+                field(&self.fmt, fields, "address"),
+            ],
+        );
 
         out
     }
 
     fn fmt_inproceedings(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let mut booktitle = fields.get("booktitle").unwrap().clone();
-        self.fmt.italics(&mut booktitle);
-        let pages = fields.get("pages").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let title = &title(&self.fmt, fields, Case::Sentence);
+        let date = EntryDate::from_fields(fields);
+        let booktitle = self.booktitle(fields);
+        let pages = fields.get("pages").cloned().unwrap_or_default();
+        let details: Vec<&str> = [booktitle.as_str(), pages.as_str()]
+            .into_iter()
+            .filter(|d| !d.is_empty())
+            .collect();
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(title);
-        out.push_str(". ");
-        out.push_str(&booktitle);
-        out.push_str(", ");
-        out.push_str(pages);
         out.push('.');
+        if !details.is_empty() {
+            out.push(' ');
+            out.push_str(&details.join(", "));
+            out.push('.');
+        }
 
         out
     }
 
     fn fmt_incollection(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let mut booktitle = fields.get("booktitle").unwrap().clone();
-        self.fmt.italics(&mut booktitle);
-        let pages = fields.get("pages").unwrap();
-        let s = String::from("sam");
-        let editors_str = fields.get("editor").unwrap_or(&s);
-        let (_tail, edrs) = and_seperated_names(editors_str).unwrap();
-        let editor_names: Vec<OwnedFullName> = edrs.into_iter().map(|n| n.into()).collect();
-        let publisher = fields.get("publisher").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let title = &title(&self.fmt, fields, Case::Sentence);
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(title);
-        out.push_str(". In ");
-        out.push_str(&fmt_editors(editor_names));
-        out.push_str(" (Eds.), ");
-        out.push_str(&booktitle);
-        out.push_str(&format!(" (pp. {}). ", pages));
-        out.push_str(publisher);
-        out.push('.');
+        out.push_str(&self.fmt_in_book(names, fields));
 
         out
     }
 
-    fn fmt_inbook(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_inbook(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let mut booktitle = fields.get("booktitle").unwrap().clone();
-        self.fmt.italics(&mut booktitle);
-        let publisher = fields.get("publisher").unwrap();
-        let pages = fields.get("pages").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let title = &title(&self.fmt, fields, Case::Sentence);
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&self.fmt_author_element(authors));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(title);
-        out.push_str(". In ");
-        out.push_str(&booktitle);
-        out.push_str(&format!(" (pp. {}). ", pages));
-        out.push_str(publisher);
-        out.push('.');
+        out.push_str(&self.fmt_in_book(names, fields));
 
         out
     }
 
     fn fmt_conference(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let publisher = fields.get("publisher").unwrap();
-        let year = fields.get("year").unwrap();
-        let mut booktitle = fields.get("booktitle").unwrap().clone();
-        self.fmt.italics(&mut booktitle);
-        let pages = fields.get("pages").unwrap();
-        let title = fields.get("title").unwrap();
-        let editors_str = fields.get("editor").unwrap();
-        let (_tail, edrs) = and_seperated_names(editors_str).unwrap();
-        let editor_names: Vec<OwnedFullName> = edrs.into_iter().map(|n| n.into()).collect();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
-        out.push_str(&format!("({}). ", &year));
+        let date = EntryDate::from_fields(fields).map(|d| d.year_only());
+        let booktitle = self.booktitle(fields);
+        let title = &title(&self.fmt, fields, Case::Sentence);
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&format!(
+            "({}). ",
+            self.fmt_entry_date(date.as_ref(), fields)
+        ));
         out.push_str(&format!("{} ", &title));
-        out.push_str(&format!("[Review of {}]. ", &title));
-        out.push_str(&format!("In {} (Ed.), ", &fmt_editors(editor_names)));
-        out.push_str(&booktitle);
-        out.push_str(&format!(" (pp. {}).", &pages));
-        out.push_str(&format!(" {}.", &publisher));
+        out.push_str(&format!("[Review of {}].", &title));
+        if !booktitle.is_empty() {
+            out.push_str(&format!(" In {}", fmt_in_editors(names)));
+            out.push_str(&booktitle);
+            if let Some(pages) = fields.get("pages") {
+                out.push_str(&format!(" (pp. {})", pages));
+            }
+            out.push('.');
+        }
+        push_sentences(&mut out, &[field(&self.fmt, fields, "publisher")]);
 
         out
     }
 
-    fn fmt_booklet(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_booklet(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
         self.fmt.italics(&mut title);
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let howpublished = fields.get("howpublished").unwrap();
+        let date = EntryDate::from_fields(fields);
 
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&title);
-        out.push_str(". ");
-        out.push_str(howpublished);
-        out.push_str(". ");
-        out.push_str(&Self::fmt_year_month(self, year, month));
         out.push('.');
+        push_sentences(
+            &mut out,
+            &[
+                field(&self.fmt, fields, "howpublished"),
+                self.fmt_entry_date(date.as_ref(), fields),
+            ],
+        );
 
         out
     }
 
-    fn fmt_book(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_book(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap().clone();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
         self.fmt.italics(&mut title);
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let publisher = fields.get("publisher").unwrap();
-        out.push_str(&Self::fmt_authors(self, authors));
-        out.push(' ');
+        let date = EntryDate::from_fields(fields);
+        let publisher = field(&self.fmt, fields, "publisher");
+        let editors = names_of(names, "editor");
+        // edited books without authors are listed under their editors
+        if authors.is_empty() {
+            out.push_str(&self.fmt_editor_element(&editors));
+        } else {
+            out.push_str(&self.fmt_author_element(authors));
+        }
+        out.push('(');
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str("). ");
+        out.push_str(&title);
+        let contributors = fmt_contributors(names, !authors.is_empty());
+        if !contributors.is_empty() {
+            out.push_str(&format!(" ({})", contributors.join("; ")));
+        }
+        out.push('.');
+        push_sentences(&mut out, &[publisher]);
+
+        out
+    }
+
+    fn fmt_online(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let mut out = String::new();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        let organization = field(&self.fmt, fields, "organization");
+        out.push_str(&self.fmt_author_element(authors));
+        out.push('(');
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str("). ");
+        out.push_str(&title);
+        out.push('.');
+        if !organization.is_empty() {
+            out.push_str(&format!(" {}.", organization));
+        }
+        out.push_str(&self.retrievable(fields));
+
+        out
+    }
+
+    fn fmt_software(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let mut out = String::new();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        let publisher = field(&self.fmt, fields, "publisher");
+        out.push_str(&self.fmt_author_element(authors));
+        out.push('(');
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str("). ");
+        out.push_str(&title);
+        if let Some(v) = fields.get("version") {
+            out.push_str(&format!(" (Version {})", v));
+        }
+        out.push_str(" [Computer software].");
+        if !publisher.is_empty() {
+            out.push_str(&format!(" {}.", publisher));
+        }
+        out.push_str(&self.retrievable(fields));
+
+        out
+    }
+
+    fn fmt_dataset(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let mut out = String::new();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        let publisher = field(&self.fmt, fields, "publisher");
+        out.push_str(&self.fmt_author_element(authors));
+        out.push('(');
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str("). ");
+        out.push_str(&title);
+        out.push_str(" [Data set].");
+        if !publisher.is_empty() {
+            out.push_str(&format!(" {}.", publisher));
+        }
+        out.push_str(&self.retrievable(fields));
+
+        out
+    }
+
+    fn fmt_patent(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let mut out = String::new();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        let kind = fields
+            .get("type")
+            .map(|t| self.fmt.markup(t))
+            .unwrap_or("Patent".to_string());
+        let patent = match fields.get("number") {
+            Some(number) => format!("{} No. {}", kind, number),
+            None => kind,
+        };
+        out.push_str(&self.fmt_author_element(authors));
+        out.push('(');
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str("). ");
+        out.push_str(&title);
+        out.push_str(&format!(" ({}).", patent));
+        out.push_str(&self.retrievable(fields));
+
+        out
+    }
+
+    fn fmt_collection(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String {
+        let mut out = String::new();
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        let editor_names = names_of(names, "editor");
+        let publisher = field(&self.fmt, fields, "publisher");
+        out.push_str(&self.fmt_editor_element(&editor_names));
         out.push('(');
-        out.push_str(&Self::fmt_year_month(self, year, month));
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
         out.push_str("). ");
         out.push_str(&title);
-        out.push_str(". ");
-        out.push_str(publisher);
         out.push('.');
+        push_sentences(&mut out, &[publisher]);
+
+        out
+    }
+
+    fn fmt_periodical(&self, _names: &NameLists, fields: &BTreeMap<String, String>) -> String {
+        let mut out = String::new();
+        let mut title = title(&self.fmt, fields, Case::Title);
+        if let Some(v) = fields.get("volume") {
+            title.push_str(&format!(", {}", v));
+        }
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&title);
+        if let Some(n) = fields.get("number") {
+            out.push_str(&format!(" ({})", n));
+        }
+        out.push_str(" (");
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str(").");
 
         out
     }
 
-    fn fmt_year_month(&self, year: Option<&String>, month: Option<&String>) -> String {
+    fn fmt_standard(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        match (year, month) {
-            (None, None) => out.push_str("n.d."),
-            (None, Some(_)) => out.push_str("n.d."),
+        let mut title = title(&self.fmt, fields, Case::Sentence);
+        self.fmt.italics(&mut title);
+        let date = EntryDate::from_fields(fields);
+        let organization = field(&self.fmt, fields, "organization");
+        // standards are usually listed under the organization that publishes them
+        if authors.is_empty() && !organization.is_empty() {
+            out.push_str(&format!("{}. ", organization));
+        } else {
+            out.push_str(&self.fmt_author_element(authors));
+        }
+        out.push('(');
+        out.push_str(&self.fmt_entry_date(date.as_ref(), fields));
+        out.push_str("). ");
+        out.push_str(&title);
+        if let Some(n) = fields.get("number") {
+            out.push_str(&format!(" ({})", n));
+        }
+        out.push('.');
+        out.push_str(&self.retrievable(fields));
+
+        out
+    }
 
-            (Some(y), None) => {
-                out.push_str(y);
+    fn fmt_date(&self, date: Option<&EntryDate>) -> String {
+        match date {
+            None => "n.d.".to_string(),
+            Some(EntryDate::Single { date, circa }) => {
+                format!(
+                    "{}{}",
+                    if *circa { "ca. " } else { "" },
+                    fmt_single_date(date)
+                )
             }
-            (Some(y), Some(m)) => {
-                // years generally don't get represented as anything other than number so unwrapping here is fine
-                let y_parsed = y.parse::<i32>().unwrap();
-                let m_parsed = m.parse::<u32>();
-                let date_formatted = match m_parsed {
-                    Ok(m) => {
-                        let date = NaiveDate::from_ymd_opt(y_parsed, m, 1).unwrap();
-                        date.format("%B").to_string()
+            Some(EntryDate::Range { start, end, circa }) => {
+                let range = match end {
+                    // e.g. 2019, January–March
+                    Some(end) if end.year == start.year && start.month.is_some() => {
+                        format!("{}–{}", fmt_single_date(start), fmt_month_day(end))
                     }
-                    // if it's not a number just capitalise the first letter
-                    Err(_) => m[0..1].to_uppercase() + &m[1..],
+                    Some(end) if end.year == start.year => end.year.to_string(),
+                    Some(end) => format!("{}–{}", start.year, end.year),
+                    None => format!("{}–present", start.year),
                 };
-                out.push_str(y);
-                out.push_str(", ");
-                out.push_str(&date_formatted);
+                format!("{}{}", if *circa { "ca. " } else { "" }, range)
             }
-        };
-
-        out
+            Some(EntryDate::Literal(l)) => l.clone(),
+        }
     }
 
-    fn fmt_article(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
-        let title = fields.get("title").unwrap().clone();
-        let volume = fields.get("volume").unwrap_or(&String::new()).clone();
+    fn fmt_article(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let title = title(&self.fmt, fields, Case::Sentence);
         let pages = fields.get("pages");
-        let number = fields.get("number").unwrap_or(&String::new()).clone();
-        let mut journal = fields.get("journal").unwrap_or(&String::new()).clone();
-        journal.push_str(&format!(", {}", volume));
-        self.fmt.italics(&mut journal);
-        let year = fields.get("year").map(|s| s.to_string());
+        // e.g. "Journal, 12 (3)" with the journal and volume in italics
+        let mut journal = field(&self.fmt, fields, "journal");
+        if let Some(volume) = fields.get("volume") {
+            if !journal.is_empty() {
+                journal.push_str(", ");
+            }
+            journal.push_str(volume);
+        }
+        if !journal.is_empty() {
+            self.fmt.italics(&mut journal);
+        }
+        if let Some(number) = fields.get("number") {
+            journal.push_str(&format!(" ({})", number));
+        }
+        let source: Vec<String> = [Some(journal), pages.cloned()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.trim().is_empty())
+            .collect();
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
         let doi = fields.get("doi");
         let mut out = String::new();
-        out.push_str(&Self::fmt_authors(self, authors.clone()));
-        out.push(' ');
-        out.push_str(&fmt_pub_date(year));
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&format!(
+            "({}).",
+            self.fmt_entry_date(year.as_ref(), fields)
+        ));
         out.push(' ');
         out.push_str(&fmt_title(title));
-        out.push(' ');
-        out.push_str(&fmt_journal(journal));
-        out.push_str(&format!(" ({}),", number));
-        out.push(' ');
-        if let Some(p) = pages {
-            out.push_str(&fmt_pages(p));
-        };
+        if !source.is_empty() {
+            out.push(' ');
+            out.push_str(&format!("{}.", source.join(", ").trim()));
+        }
 
         if let Some(d) = doi {
-            if pages.is_some() {
-                out.push(' ');
-            }
+            out.push(' ');
             let mut cl = d.clone();
             self.fmt.hyperlink(&mut cl);
 
@@ -372,7 +582,18 @@ impl<T: Formatter> Stylizer for ApaStylizer<T> {
         out
     }
 
-    fn fmt_authors(&self, mut authors: Vec<OwnedFullName>) -> String {
+    fn fmt_authors(&self, authors: &[OwnedFullName]) -> String {
+        let mut authors = authors.to_vec();
+        if truncate_others(&mut authors) && !authors.is_empty() {
+            return format!(
+                "{}, et al.",
+                authors
+                    .into_iter()
+                    .map(fmt_single_author)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+        }
         match &authors.len() {
             0 => String::new(),
             1 => {
@@ -415,16 +636,244 @@ impl<T: Formatter> Stylizer for ApaStylizer<T> {
             }
         }
     }
+
+    fn fmt_citation(
+        &self,
+        cited: Vec<Cited>,
+        mode: CitationMode,
+        prenote: Option<&str>,
+        postnote: Option<&str>,
+    ) -> String {
+        let year = |c: &Cited| {
+            let date = EntryDate::from_fields(&c.entry.fields).map(|d| d.year_only());
+            with_year_suffix(self.fmt_date(date.as_ref()), c.year_suffix.as_deref())
+        };
+        match mode {
+            CitationMode::Narrative => {
+                // the postnote goes with the last year, e.g. "Doe (2020, p. 5)"
+                let parts: Vec<String> = cited
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let year = match i + 1 == cited.len() {
+                            true => with_notes(year(c), None, postnote),
+                            false => year(c),
+                        };
+                        format!("{} ({})", cited_names(c, "and"), year)
+                    })
+                    .collect();
+                with_notes(parts.join("; "), prenote, None)
+            }
+            CitationMode::Author => {
+                let names: Vec<String> = cited.iter().map(|c| cited_names(c, "and")).collect();
+                with_notes(names.join("; "), prenote, postnote)
+            }
+            CitationMode::Year => {
+                let years: Vec<String> = cited.iter().map(year).collect();
+                with_notes(years.join("; "), prenote, postnote)
+            }
+            CitationMode::SuppressAuthor => {
+                let years: Vec<String> = cited.iter().map(year).collect();
+                format!("({})", with_notes(years.join("; "), prenote, postnote))
+            }
+            _ => {
+                let parts: Vec<String> = cited
+                    .iter()
+                    .map(|c| format!("{}, {}", cited_names(c, "&"), year(c)))
+                    .collect();
+                format!("({})", with_notes(parts.join("; "), prenote, postnote))
+            }
+        }
+    }
+
+    fn fmt_bibliography(&self, cited: Vec<Cited>) -> Vec<String> {
+        cited
+            .into_iter()
+            .map(|c| self.fmt_reference(&c.dated_entry()))
+            .collect()
+    }
+}
+
+impl<T: Formatter> ApaStylizer<T> {
+    /// The authors that start a reference, which end in a period like "Doe, J. " or "World
+    /// Health Organization. ", or nothing for works without authors
+    fn fmt_author_element(&self, authors: &[OwnedFullName]) -> String {
+        let mut out = self.fmt_authors(authors);
+        if out.is_empty() {
+            return out;
+        }
+        // personal names already end in an initial
+        if !out.ends_with('.') {
+            out.push('.');
+        }
+        out.push(' ');
+        out
+    }
+
+    /// e.g. "B. Editor (Ed.). " for works that are listed under their editors
+    fn fmt_editor_element(&self, editors: &[OwnedFullName]) -> String {
+        match editors.is_empty() {
+            true => String::new(),
+            false => format!(
+                "{} ({}). ",
+                self.fmt_authors(editors),
+                editor_label(editors)
+            ),
+        }
+    }
+
+    /// the italicised title of the book an entry is part of, if it has one
+    fn booktitle(&self, fields: &BTreeMap<String, String>) -> String {
+        let mut booktitle = field(&self.fmt, fields, "booktitle");
+        if !booktitle.is_empty() {
+            self.fmt.italics(&mut booktitle);
+        }
+        booktitle
+    }
+
+    /// e.g. ". In B. Editor (Ed.), Book (pp. 1-10). Publisher." after the title of a chapter
+    fn fmt_in_book(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String {
+        let mut out = String::new();
+        let booktitle = self.booktitle(fields);
+        if !booktitle.is_empty() {
+            out.push_str(". In ");
+            out.push_str(&fmt_in_editors(names));
+            out.push_str(&booktitle);
+        }
+        let mut details = fmt_contributors(names, false);
+        if let Some(pages) = fields.get("pages") {
+            details.push(format!("pp. {}", pages));
+        }
+        if !details.is_empty() {
+            out.push_str(&format!(" ({})", details.join("; ")));
+        }
+        out.push('.');
+        push_sentences(&mut out, &[field(&self.fmt, fields, "publisher")]);
+        out
+    }
+
+    /// the date of an entry with the letter that sets it apart from other works by the
+    /// same authors in the same year, see [`Cited::year_suffix`]
+    fn fmt_entry_date(
+        &self,
+        date: Option<&EntryDate>,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        with_year_suffix(
+            self.fmt_date(date),
+            fields.get("extradate").map(String::as_str),
+        )
+    }
+
+    /// the doi or url an electronic source can be retrieved from, if it has one
+    fn retrievable(&self, fields: &BTreeMap<String, String>) -> String {
+        let (link, accessed) = match (fields.get("doi"), fields.get("url")) {
+            (Some(doi), _) => (doi, None),
+            (None, Some(url)) => (url, EntryDate::urldate(fields)),
+            (None, None) => return String::new(),
+        };
+        let mut link = link.clone();
+        self.fmt.hyperlink(&mut link);
+        match accessed {
+            Some(date) => format!(
+                " Retrieved {}, from {}",
+                fmt_accessed(&date, self.fmt_date(Some(&date))),
+                link
+            ),
+            None => format!(" {}", link),
+        }
+    }
+}
+
+/// Put the letter that sets apart works by the same authors in the same year after the
+/// year, e.g. "2001a, May 5". Undated works become "n.d.-a".
+fn with_year_suffix(date: String, suffix: Option<&str>) -> String {
+    let suffix = match suffix {
+        Some(s) => s,
+        None => return date,
+    };
+    match date.find(|c: char| c.is_ascii_digit()) {
+        Some(start) => {
+            let end = date[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(date.len(), |len| start + len);
+            format!("{}{}{}", &date[..end], suffix, &date[end..])
+        }
+        None => format!("{}-{}", date, suffix),
+    }
+}
+
+/// retrieval dates are written like "July 12, 2020"
+fn fmt_accessed(date: &EntryDate, formatted: String) -> String {
+    match date {
+        EntryDate::Single { date, circa: false } if date.month.is_some() => {
+            let month_day = fmt_month_day(date);
+            format!("{}, {}", month_day, date.year)
+        }
+        _ => formatted,
+    }
+}
+
+/// e.g. "July 12" or "July" without the year
+fn fmt_month_day(date: &Date) -> String {
+    match (date.month, date.day) {
+        (Some(m), Some(d)) => format!("{} {}", m.name(), d),
+        (Some(m), None) => m.name().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// e.g. "2015, July 12", "2015, July" or "2015"
+fn fmt_single_date(date: &Date) -> String {
+    match date.month {
+        Some(_) => format!("{}, {}", date.year, fmt_month_day(date)),
+        None => date.year.to_string(),
+    }
 }
 
-fn fmt_pages(pages: &String) -> String {
-    format!("{}.", pages)
+/// add the parts that aren't empty as sentences of their own, e.g. " Publisher. Address."
+fn push_sentences(out: &mut String, parts: &[String]) {
+    for part in parts.iter().filter(|p| !p.is_empty()) {
+        out.push(' ');
+        out.push_str(part);
+        out.push('.');
+    }
+}
+
+/// e.g. "B. Editor (Ed.), " before the title of the book a chapter is in
+fn fmt_in_editors(names: &NameLists) -> String {
+    let editors = names_of(names, "editor");
+    if editors.is_empty() {
+        return String::new();
+    }
+    let label = editor_label(&editors);
+    format!("{} ({}), ", fmt_editors(editors), label)
 }
-fn fmt_journal(journal: String) -> String {
-    journal.to_string()
+
+/// e.g. ["B. Editor, Ed.", "C. Translator, Trans."] for inside the parentheses after a title
+fn fmt_contributors(names: &NameLists, with_editors: bool) -> Vec<String> {
+    let mut out = Vec::new();
+    let editors = names_of(names, "editor");
+    if with_editors && !editors.is_empty() {
+        let label = editor_label(&editors);
+        out.push(format!("{}, {}", fmt_editors(editors), label));
+    }
+    let translators = names_of(names, "translator");
+    if !translators.is_empty() {
+        out.push(format!("{}, Trans.", fmt_editors(translators)));
+    }
+    out
 }
 
 fn fmt_editors(mut authors: Vec<OwnedFullName>) -> String {
+    if truncate_others(&mut authors) && !authors.is_empty() {
+        let listed = authors
+            .into_iter()
+            .map(fmt_single_editor)
+            .collect::<Vec<String>>();
+        let sep = if listed.len() == 1 { " " } else { ", " };
+        return format!("{}{}et al.", listed.join(", "), sep);
+    }
     match &authors.len() {
         0 => String::new(),
         1 => {
@@ -443,12 +892,12 @@ fn fmt_editors(mut authors: Vec<OwnedFullName>) -> String {
         3..=21 => {
             let last_author = authors.remove(authors.len() - 1);
             format!(
-                "{} & {}",
+                "{}, & {}",
                 authors
                     .into_iter()
-                    .map(fmt_single_author)
+                    .map(fmt_single_editor)
                     .collect::<Vec<String>>()
-                    .join(" "),
+                    .join(", "),
                 fmt_single_editor(last_author)
             )
         }
@@ -459,60 +908,59 @@ fn fmt_editors(mut authors: Vec<OwnedFullName>) -> String {
                 "{},...{}",
                 listed_authors
                     .into_iter()
-                    .map(fmt_single_author)
+                    .map(fmt_single_editor)
                     .collect::<Vec<String>>()
-                    .join(" "),
+                    .join(", "),
                 fmt_single_editor(last_author)
             )
         }
     }
 }
 
-fn fmt_pub_date(year: Option<String>) -> String {
-    format!("({}).", year.unwrap_or_else(|| "n.d.".to_string()))
-}
 fn fmt_title(title: String) -> String {
     format!("{}.", title)
 }
 
 fn fmt_single_author(name: OwnedFullName) -> String {
+    // organisations are never abbreviated
+    if name.kind == NameKind::Literal {
+        return name.last.join(" ");
+    }
     let mut out = String::new();
     if !name.last.is_empty() {
-        out.push_str(&name.last.join(" "));
+        out.push_str(&surname(&name));
     }
     if !name.first.is_empty() {
         if !name.last.is_empty() {
             out.push_str(", ");
         }
-        out.push_str(
-            &name
-                .first
-                .iter()
-                .map(|n| format!("{}.", n.graphemes(true).next().unwrap()))
-                .collect::<Vec<String>>()
-                .join(" "),
-        )
+        out.push_str(&name.initials().join(" "))
     };
+    if let Some(jr) = jr(&name) {
+        out.push_str(", ");
+        out.push_str(&jr);
+    }
     out
 }
 
 fn fmt_single_editor(name: OwnedFullName) -> String {
+    // organisations are never abbreviated
+    if name.kind == NameKind::Literal {
+        return name.last.join(" ");
+    }
     let mut out = String::new();
     if !name.first.is_empty() {
-        out.push_str(
-            &name
-                .first
-                .iter()
-                .map(|n| format!("{}.", n.graphemes(true).next().unwrap()))
-                .collect::<Vec<String>>()
-                .join(" "),
-        )
+        out.push_str(&name.initials().join(" "))
     };
     if !name.last.is_empty() {
         if !name.first.is_empty() {
             out.push(' ');
         }
-        out.push_str(&name.last.join(" "));
+        out.push_str(&surname(&name));
+    }
+    if let Some(jr) = jr(&name) {
+        out.push(' ');
+        out.push_str(&jr);
     }
     out
 }
@@ -520,9 +968,13 @@ fn fmt_single_editor(name: OwnedFullName) -> String {
 mod test {
     use std::path::PathBuf;
 
-    use crate::{formaters::plain::PlainTextFormatter, ops::bibligraphy::Bibliography};
+    use crate::{
+        formaters::{markdown::MarkdownFormatter, plain::PlainTextFormatter},
+        ops::bibligraphy::Bibliography,
+    };
 
     use super::*;
+    use crate::styles::{ReferenceStyle, SortOrder};
     use anyhow::Result;
 
     #[test]
@@ -531,10 +983,13 @@ mod test {
             first: vec!["Ada".to_string(), "Maria".to_string()],
             last: vec!["Lovelace".to_string(), "Augusta".to_string()],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
+            langid: None,
             title: vec![],
         };
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(vec![author]);
+        let formated = stylizer.fmt_authors(&[author]);
         assert_eq!(formated, "Lovelace Augusta, A. M.");
 
         Ok(())
@@ -546,17 +1001,23 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(formated, "Lovelace Augusta, A. M., & Noether, A. E.");
 
         Ok(())
@@ -568,23 +1029,32 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(
             formated,
             "Lovelace Augusta, A. M., Noether, A. E., & Germain, S."
@@ -599,149 +1069,221 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sofia".to_string()],
                 last: vec!["Kovalevskaya".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Dorothy".to_string()],
                 last: vec!["Vaughn".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Maryam".to_string()],
                 last: vec!["Mirzakhani".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sofia".to_string()],
                 last: vec!["Kovalevskaya".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Dorothy".to_string()],
                 last: vec!["Vaughn".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Maryam".to_string()],
                 last: vec!["Mirzakhani".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sofia".to_string()],
                 last: vec!["Kovalevskaya".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Dorothy".to_string()],
                 last: vec!["Vaughn".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Maryam".to_string()],
                 last: vec!["Mirzakhani".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sofia".to_string()],
                 last: vec!["Kovalevskaya".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Dorothy".to_string()],
                 last: vec!["Vaughn".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Maryam".to_string()],
                 last: vec!["Mirzakhani".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(
             formated,
             "Lovelace Augusta, A. M., Noether, A. E., Germain, S., Kovalevskaya, S., Vaughn, D., Mirzakhani, M., Lovelace Augusta, A. M., Noether, A. E., Germain, S., Kovalevskaya, S., Vaughn, D., Mirzakhani, M., Lovelace Augusta, A. M., Noether, A. E., Germain, S., Kovalevskaya, S., Vaughn, D., Mirzakhani, M., Lovelace Augusta, A. M.,...Mirzakhani, M."
@@ -755,7 +1297,7 @@ mod test {
         let key = "breiman2001";
         let formatted_citation = "Breiman, L. (2001). Random forests. Machine learning, 45 (1), 5-32. https://doi.org/10.1023/a:1010933404324";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -764,9 +1306,9 @@ mod test {
     #[test]
     fn bacterial_formatted_citation() -> Result<()> {
         let key = "10.1093/femsec/fiw174";
-        let formatted_citation= "Liao, J., Cao, X., Zhao, L., Wang, J., Gao, Z., Wang, M. C., & Huang, Y. (2016). The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists. FEMS Microbiology Ecology, 92 (11), https://doi.org/10.1093/femsec/fiw174";
+        let formatted_citation= "Liao, J., Cao, X., Zhao, L., Wang, J., Gao, Z., Wang, M. C., & Huang, Y. (2016). The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists. FEMS Microbiology Ecology, 92 (11). https://doi.org/10.1093/femsec/fiw174";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -777,7 +1319,7 @@ mod test {
         let key = "article";
         let formatted_citation= "Cohen, P. J. (1963). The independence of the continuum hypothesis. Proceedings of the National Academy of Sciences, 50 (6), 1143-1148.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -786,9 +1328,9 @@ mod test {
     #[test]
     fn book_formatted_citation() -> Result<()> {
         let key = "book";
-        let formatted_citation= "Susskind, L., & Hrabovsky, G. (2014). Classical mechanics: the theoretical minimum. Penguin Random House.";
+        let formatted_citation= "Susskind, L., & Hrabovsky, G. (2014). Classical mechanics: The theoretical minimum. Penguin Random House.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -797,9 +1339,9 @@ mod test {
     #[test]
     fn booklet_formatted_citation() -> Result<()> {
         let key = "booklet";
-        let formatted_citation= "Swetla, M. Canoe tours in Sweden. Distributed at the Stockholm Tourist Office. 2015, July.";
+        let formatted_citation= "Swetla, M. Canoe tours in sweden. Distributed at the Stockholm Tourist Office. 2015, July.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -810,7 +1352,7 @@ mod test {
         let key = "inbook";
         let formatted_citation= "Urry, L. A., Cain, M. L., Wasserman, S. A., Minorsky, P. V., & Reece, J. B. (2016). Photosynthesis. In Campbell biology (pp. 187-221). Pearson.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -821,7 +1363,7 @@ mod test {
         let key = "incollection";
         let formatted_citation= "Shapiro, H. M. (2018). Flow cytometry: The glass is half full. In T. S. Hawley & R. G. Hawley (Eds.), Flow cytometry protocols (pp. 1-10). Springer.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -832,7 +1374,7 @@ mod test {
         let key = "inproceedings";
         let formatted_citation= "Holleis, P., Wagner, M., & Koolwaaij, J. (2010). Studying mobile context-aware social services in the wild. Proc. of the 6th Nordic Conf. on Human-Computer Interaction, 207-216.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -843,7 +1385,7 @@ mod test {
         let key = "manual";
         let formatted_citation= "R Core Team. (2018). R: A language and environment for statistical computing. R Foundation for Statistical Computing. Vienna, Austria.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -854,7 +1396,7 @@ mod test {
         let key = "mastersthesis";
         let formatted_citation= "Tang, J. (1996, September). Spin structure of the nucleon in the asymptotic limit [Master's thesis, Massachusetts Institute of Technology].";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -866,7 +1408,7 @@ mod test {
         let formatted_citation =
             "NASA. (2015). Pluto: The 'other' red planet [Accessed: 2018-12-06].";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -877,7 +1419,7 @@ mod test {
         let key = "phdthesis";
         let formatted_citation= "Rempel, R. C. (1956, June). Relaxation effects for coupled nuclear spins [Doctoral dissertation, Stanford University].";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -888,7 +1430,7 @@ mod test {
         let key = "proceedings";
         let formatted_citation= "Stepney, S., & Verlan, S. (Eds.). (2018). Proceedings of the 17th international conference on computation and natural computation, fontainebleau, france (Vol. 10867). Springer.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -897,9 +1439,9 @@ mod test {
     #[test]
     fn techreport_formatted_citation() -> Result<()> {
         let key = "techreport";
-        let formatted_citation= "Bennett, V., Bowman, K., & Wright, S. (2018, September). Wasatch Solar Project final report (tech. rep. No. DOE-SLC-6903-1). Salt Lake City Corporation. Salt Lake City, UT.";
+        let formatted_citation= "Bennett, V., Bowman, K., & Wright, S. (2018, September). Wasatch solar project final report (tech. rep. No. DOE-SLC-6903-1). Salt Lake City Corporation. Salt Lake City, UT.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -910,7 +1452,7 @@ mod test {
         let key = "unpublished";
         let formatted_citation = "Suresh, M. (2006). Evolution: A revised theory.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -919,12 +1461,496 @@ mod test {
     #[test]
     fn conference_formatted_citation() -> Result<()> {
         let key = "conference";
-        let formatted_citation= "Smith, J., & Doe, J. (2022). The Effects of Climate Change [Review of The Effects of Climate Change]. In B. Johnson (Ed.), Proceedings of the Annual Conference on Climate Change (pp. 55-62). Springer.";
+        let formatted_citation= "Smith, J., & Doe, J. (2022). The effects of climate change [Review of The effects of climate change]. In B. Johnson (Ed.), Proceedings of the Annual Conference on Climate Change (pp. 55-62). Springer.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = ApaStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
         Ok(())
     }
+
+    #[test]
+    fn latex_formatting_in_title() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@book{code,
+                author = {Jane Doe},
+                title = {Writing \texttt{Rust}, see \url{https://rust-lang.org}},
+                publisher = {{\em No} Starch},
+                year = 2020,
+            }",
+        )?;
+        let entry = bib.get_entry("code").unwrap();
+        let stylizer = ApaStylizer::<MarkdownFormatter>::default();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "Doe, J. (2020). *Writing `Rust`, see [https://rust-lang.org](https://rust-lang.org)*. *No* Starch."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn title_in_sentence_case() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@article{dna,
+                author = {Jane Doe},
+                title = {{DNA} Sequencing With {B}ayesian Methods: A Review},
+                journal = {Journal of Genomics},
+                volume = 3,
+                number = 1,
+                pages = {1--10},
+                year = 2021,
+            }",
+        )?;
+        let entry = bib.get_entry("dna").unwrap();
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "Doe, J. (2021). DNA sequencing with Bayesian methods: A review. Journal of Genomics, 3 (1), 1–10."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minimal_entries() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@report{minimal,
+                author = {Jane Doe},
+                title = {Widgets},
+                institution = {ACME},
+                year = 2020,
+            }
+            @report{typed,
+                author = {Jane Doe},
+                title = {Widgets},
+                type = {White paper},
+                number = 7,
+                institution = {ACME},
+                address = {Springfield},
+                year = 2020,
+            }
+            @inproceedings{paper,
+                author = {Jane Doe},
+                title = {A Paper},
+                year = 2020,
+            }",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("minimal").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "Doe, J. (2020). Widgets (tech. rep.). ACME."
+        );
+        let entry = bib.get_entry("typed").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "Doe, J. (2020). Widgets (White paper No. 7). ACME. Springfield."
+        );
+        let entry = bib.get_entry("paper").unwrap();
+        assert_eq!(stylizer.fmt_reference(entry), "Doe, J. (2020). A paper.");
+        Ok(())
+    }
+
+    #[test]
+    fn von_part_is_printed() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@book{symphony,
+                author = {Ludwig van Beethoven and O'Neil, Cathy},
+                title = {Symphony No. 9},
+                publisher = {Schott},
+                address = {Mainz},
+                year = 1826,
+            }",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("symphony").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "van Beethoven, L., & O'Neil, C. (1826). Symphony no. 9. Schott."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn biblatex_entry_types() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@online{site,
+                author = {Jane Doe},
+                title = {the {cite-me-bro} homepage},
+                organization = {GitHub},
+                year = 2023,
+                url = {https://github.com/savente93/cite-me-bro},
+            }
+            @software{tool,
+                author = {Jane Doe},
+                title = {{cite-me-bro}},
+                version = {0.1.0},
+                publisher = {Zenodo},
+                year = 2023,
+            }
+            @thesis{thesis,
+                author = {Jane Doe},
+                title = {on citations},
+                type = {phdthesis},
+                institution = {Delft University of Technology},
+                year = 2019,
+            }
+            @thesis{bachelor,
+                author = {Jane Doe},
+                title = {on references},
+                type = {Bachelor's thesis},
+                school = {Delft University of Technology},
+                year = 2015,
+            }
+            @patent{patent,
+                author = {Jane Doe},
+                title = {a citation machine},
+                type = {U.S. Patent},
+                number = {1234567},
+                year = 2020,
+            }
+            @artwork{other,
+                author = {Jane Doe},
+                title = {a painting},
+                year = 2021,
+            }",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(
+            formatted("site"),
+            "Doe, J. (2023). The cite-me-bro homepage. GitHub. https://github.com/savente93/cite-me-bro"
+        );
+        assert_eq!(
+            formatted("tool"),
+            "Doe, J. (2023). cite-me-bro (Version 0.1.0) [Computer software]. Zenodo."
+        );
+        assert_eq!(
+            formatted("thesis"),
+            "Doe, J. (2019). On citations [Doctoral dissertation, Delft University of Technology]."
+        );
+        assert_eq!(
+            formatted("bachelor"),
+            "Doe, J. (2015). On references [Bachelor's thesis, Delft University of Technology]."
+        );
+        assert_eq!(
+            formatted("patent"),
+            "Doe, J. (2020). A citation machine (U.S. Patent No. 1234567)."
+        );
+        assert_eq!(formatted("other"), "Doe, J. (2021). A painting.");
+        Ok(())
+    }
+
+    #[test]
+    fn dates() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@misc{range,
+                author = {Jane Doe},
+                title = {Range},
+                date = {2019-01/2019-03},
+            }
+            @misc{years,
+                author = {Jane Doe},
+                title = {Years},
+                date = {2019/},
+            }
+            @misc{day,
+                author = {Jane Doe},
+                title = {Day},
+                date = {2020-05-12},
+            }
+            @misc{circa,
+                author = {Jane Doe},
+                title = {Circa},
+                date = {1850~},
+            }
+            @misc{suffix,
+                author = {Jane Doe},
+                title = {Suffix},
+                year = {2021a},
+                month = 5,
+            }
+            @misc{forthcoming,
+                author = {Jane Doe},
+                title = {Forthcoming},
+                year = {forthcoming},
+                month = jan,
+            }
+            @online{accessed,
+                author = {Jane Doe},
+                title = {Accessed},
+                year = 2020,
+                url = {https://example.com},
+                urldate = {2023-10-01},
+            }",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(formatted("range"), "Doe, J. (2019, January–March). Range.");
+        assert_eq!(formatted("years"), "Doe, J. (2019–present). Years.");
+        assert_eq!(formatted("day"), "Doe, J. (2020, May 12). Day.");
+        assert_eq!(formatted("circa"), "Doe, J. (ca. 1850). Circa.");
+        assert_eq!(formatted("suffix"), "Doe, J. (May 2021a). Suffix.");
+        assert_eq!(
+            formatted("forthcoming"),
+            "Doe, J. (forthcoming). Forthcoming."
+        );
+        assert_eq!(
+            formatted("accessed"),
+            "Doe, J. (2020). Accessed. Retrieved October 1, 2023, from https://example.com"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn month_representations() -> Result<()> {
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        for month in ["jan", "{jan}", "{January}", "1", "{01}", "{Jan.}"] {
+            let bib = Bibliography::from_bib_str(&format!(
+                "@misc{{foo, author = {{Jane Doe}}, title = {{Foo}}, year = 2020, month = {}}}",
+                month
+            ))?;
+            let entry = bib.get_entry("foo").unwrap();
+            assert_eq!(
+                stylizer.fmt_reference(entry),
+                "Doe, J. (2020, January). Foo."
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn editors_and_translators() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@book{edited,
+                editor = {Jane Doe and John Smith},
+                title = {An edited book},
+                publisher = {Springer},
+                year = 2020,
+            }
+            @book{translated,
+                author = {Leo Tolstoy},
+                editor = {Jane Doe},
+                translator = {Louise Maude and Aylmer Maude},
+                title = {War and peace},
+                publisher = {Oxford University Press},
+                year = 2010,
+            }
+            @incollection{chapter,
+                author = {Jane Doe},
+                title = {A chapter},
+                booktitle = {A collection},
+                editor = {John Smith},
+                translator = {Ann Jones},
+                pages = {1--10},
+                publisher = {Springer},
+                year = 2020,
+            }",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(
+            formatted("edited"),
+            "Doe, J., & Smith, J. (Eds.). (2020). An edited book. Springer."
+        );
+        assert_eq!(
+            formatted("translated"),
+            "Tolstoy, L. (2010). War and peace (J. Doe, Ed.; L. Maude & A. Maude, Trans.). Oxford University Press."
+        );
+        assert_eq!(
+            formatted("chapter"),
+            "Doe, J. (2020). A chapter. In J. Smith (Ed.), A collection (A. Jones, Trans.; pp. 1–10). Springer."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn jr_name_parts() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo, author = {Doe, Jr., John and Smith, III, Jane}, title = {Foo}, year = 2020}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "Doe, J., Jr., & Smith, J., III. (2020). Foo."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn organisations_as_authors() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo, author = {{World Health Organization} and Jane Doe}, title = {Foo}, year = 2020}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "World Health Organization, & Doe, J. (2020). Foo."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn organisation_as_only_author() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r#"@book{who, author = {{World Health Organization}}, title = {A Book}, year = 2020}
+            @book{bank,
+                author = {{{\"O}sterreichische Nationalbank}},
+                title = {Annual Report},
+                publisher = {OeNB},
+                year = 2021,
+            }"#,
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(
+            formatted("who"),
+            "World Health Organization. (2020). A book."
+        );
+        assert_eq!(
+            formatted("bank"),
+            "Österreichische Nationalbank. (2021). Annual report. OeNB."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn and_others() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{one, author = {Jane Doe and others}, title = {Foo}, year = 2020}
+            @misc{two, author = {Jane Doe and John Smith and others}, title = {Foo}, year = 2020}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(formatted("one"), "Doe, J., et al. (2020). Foo.");
+        assert_eq!(formatted("two"), "Doe, J., Smith, J., et al. (2020). Foo.");
+        Ok(())
+    }
+
+    #[test]
+    fn compound_initials() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo, author = {Jean-Paul Sartre and Christian Chalupa}, title = {Foo}, year = 2020, langid = {czech}}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "Sartre, J.-P., & Chalupa, Ch. (2020). Foo."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reference_list_order() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{later, author = {Jane Doe}, title = {Foo}, year = 2021}
+            @misc{coauthored, author = {Jane Doe and Ann Able}, title = {Bar}, year = 2019}
+            @misc{earlier, author = {Jane Doe}, title = {Baz}, year = 2020}
+            @misc{undated, author = {Jane Doe}, title = {Qux}}
+            @misc{org, author = {{The Cochrane Collaboration}}, title = {Review}, year = 2020}
+            @misc{von, author = {Ludwig van Beethoven}, title = {Symphony}, year = 1808}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let sorted = |order: SortOrder| {
+            let mut cited: Vec<Cited> = ["later", "coauthored", "earlier", "undated", "org", "von"]
+                .into_iter()
+                .enumerate()
+                .map(|(i, key)| Cited::new(bib.get_entry(key).unwrap(), i + 1))
+                .collect();
+            ReferenceStyle::APA.sort(&mut cited, order);
+            stylizer.fmt_bibliography(cited)
+        };
+        assert_eq!(
+            sorted(SortOrder::Style),
+            vec![
+                "van Beethoven, L. (1808). Symphony.",
+                "The Cochrane Collaboration. (2020). Review.",
+                "Doe, J. (2020). Baz.",
+                "Doe, J. (2021). Foo.",
+                "Doe, J. (n.d.). Qux.",
+                "Doe, J., & Able, A. (2019). Bar.",
+            ]
+        );
+        assert_eq!(
+            sorted(SortOrder::Year),
+            vec![
+                "van Beethoven, L. (1808). Symphony.",
+                "Doe, J., & Able, A. (2019). Bar.",
+                "The Cochrane Collaboration. (2020). Review.",
+                "Doe, J. (2020). Baz.",
+                "Doe, J. (2021). Foo.",
+                "Doe, J. (n.d.). Qux.",
+            ]
+        );
+        assert_eq!(
+            sorted(SortOrder::Key)[0],
+            "Doe, J., & Able, A. (2019). Bar."
+        );
+        assert_eq!(sorted(SortOrder::None)[0], "Doe, J. (2021). Foo.");
+        Ok(())
+    }
+
+    #[test]
+    fn sorted_by_initials_and_printed_title() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@misc{zebras, author = {Jane Smith}, title = {\emph{Zebras}}, year = 2020}
+            @misc{apes, author = {Jane Smith}, title = {Apes}, year = 2020}
+            @misc{owls, author = {John Smith}, title = {Owls}, year = 2019}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let mut cited: Vec<Cited> = ["zebras", "apes", "owls"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| Cited::new(bib.get_entry(key).unwrap(), i + 1))
+            .collect();
+        ReferenceStyle::APA.sort(&mut cited, SortOrder::Style);
+        assert_eq!(
+            stylizer.fmt_bibliography(cited),
+            vec![
+                "Smith, J. (2019). Owls.",
+                "Smith, J. (2020). Apes.",
+                "Smith, J. (2020). Zebras.",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn year_suffixes() {
+        for (date, suffix, expected) in [
+            ("2001", None, "2001"),
+            ("2001", Some("a"), "2001a"),
+            ("2020, May 5", Some("b"), "2020b, May 5"),
+            ("ca. 1850", Some("a"), "ca. 1850a"),
+            ("n.d.", Some("a"), "n.d.-a"),
+        ] {
+            assert_eq!(with_year_suffix(date.to_string(), suffix), expected);
+        }
+    }
+
+    #[test]
+    fn missing_fields_leave_no_punctuation() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@report{untitled, title = {Untitled Report}}
+            @article{bare, author = {Jane Doe}, title = {Widgets}, journal = {Gadgets}, year = 2020}
+            @patent{pending, author = {Jane Doe}, title = {Widgets}, year = 2020}
+            @thesis{thesis, author = {Jane Doe}, title = {Widgets}, type = {phdthesis}}",
+        )?;
+        let stylizer = ApaStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(
+            formatted("untitled"),
+            "(n.d.). Untitled report (tech. rep.)."
+        );
+        assert_eq!(formatted("bare"), "Doe, J. (2020). Widgets. Gadgets.");
+        assert_eq!(formatted("pending"), "Doe, J. (2020). Widgets (Patent).");
+        assert_eq!(
+            formatted("thesis"),
+            "Doe, J. (n.d.). Widgets [Doctoral dissertation]."
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,184 @@
+use crate::parsing::markup::{plain_text, Inline};
+
+/// words that aren't capitalised in title case, unless they start the title
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "over", "per", "the", "to", "upon", "via", "vs", "with",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Only the first word (also after a colon) is capitalised, e.g. "A theory of everything: Why bother"
+    Sentence,
+    /// Every word except minor ones is capitalised, e.g. "A Theory of Everything: Why Bother"
+    Title,
+}
+
+struct CaseState {
+    /// whether the next word starts a sentence (or the part after a colon)
+    sentence_start: bool,
+    /// whether the text so far ended in the middle of a word, like `{B}ayesian`
+    in_word: bool,
+}
+
+impl CaseState {
+    fn skip(&mut self, text: &str) {
+        if let Some(last) = text.chars().last() {
+            self.sentence_start = false;
+            self.in_word = is_word_char(last);
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\'' || c == '’'
+}
+
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn change_word(word: &str, case: Case, state: &CaseState) -> String {
+    match case {
+        Case::Sentence if state.sentence_start && !state.in_word => {
+            capitalise(&word.to_lowercase())
+        }
+        Case::Sentence => word.to_lowercase(),
+        Case::Title if state.in_word => word.to_string(),
+        Case::Title
+            if !state.sentence_start && MINOR_WORDS.contains(&word.to_lowercase().as_str()) =>
+        {
+            word.to_lowercase()
+        }
+        Case::Title => capitalise(word),
+    }
+}
+
+fn change_text(text: &str, case: Case, state: &mut CaseState) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        // words can contain apostrophes, but not start with them
+        if c.is_alphanumeric() || (state.in_word && is_word_char(c)) {
+            let len = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+            out.push_str(&change_word(&rest[..len], case, state));
+            state.sentence_start = false;
+            state.in_word = true;
+            rest = &rest[len..];
+        } else {
+            out.push(c);
+            state.in_word = false;
+            if ":?!".contains(c) {
+                state.sentence_start = true;
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+fn change_nodes(nodes: &[Inline], case: Case, state: &mut CaseState) -> Vec<Inline> {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Inline::Text(t) => Inline::Text(change_text(t, case, state)),
+            // like bibtex, the argument of a command counts as a braced group
+            Inline::Italic(c) | Inline::Bold(c) | Inline::Protected(c) => {
+                state.skip(&plain_text(c));
+                node.clone()
+            }
+            Inline::Verbatim(t) | Inline::Math(t) | Inline::Url(t) => {
+                state.skip(t);
+                node.clone()
+            }
+        })
+        .collect()
+}
+
+/// Change the case of the text, leaving anything protected by braces or in the argument of a
+/// formatting command, as well as verbatim text, math and urls as they are.
+pub fn change_case(nodes: &[Inline], case: Case) -> Vec<Inline> {
+    let mut state = CaseState {
+        sentence_start: true,
+        in_word: false,
+    };
+    change_nodes(nodes, case, &mut state)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parsing::markup::parse_markup;
+
+    fn cased(input: &str, case: Case) -> String {
+        plain_text(&change_case(&parse_markup(input), case))
+    }
+
+    #[test]
+    fn sentence_case() {
+        assert_eq!(
+            cased("The Art of Computer Programming", Case::Sentence),
+            "The art of computer programming"
+        );
+        assert_eq!(
+            cased(
+                "a {Bayesian} approach: {DNA} Sequencing in {B}ayesian Terms",
+                Case::Sentence
+            ),
+            "A Bayesian approach: DNA sequencing in Bayesian terms"
+        );
+        assert_eq!(
+            cased(
+                "Growth of \\emph{Small} Colonies With \\texttt{MyTool}",
+                Case::Sentence
+            ),
+            "Growth of Small colonies with MyTool"
+        );
+        assert_eq!(cased("Don't Panic", Case::Sentence), "Don't panic");
+    }
+
+    #[test]
+    fn title_case() {
+        assert_eq!(
+            cased("the art of computer programming", Case::Title),
+            "The Art of Computer Programming"
+        );
+        assert_eq!(
+            cased(
+                "an analysis OF {iPhone} use: the case for self-organizing {x}ylophones",
+                Case::Title
+            ),
+            "An Analysis of iPhone Use: The Case for Self-Organizing xylophones"
+        );
+        assert_eq!(cased("don't panic", Case::Title), "Don't Panic");
+        assert_eq!(
+            cased("the 'other' planet", Case::Title),
+            "The 'Other' Planet"
+        );
+    }
+
+    #[test]
+    fn keeps_formatting() {
+        assert_eq!(
+            change_case(&parse_markup("Of \\emph{Mice} and {Men}"), Case::Sentence),
+            vec![
+                Inline::Text("Of ".to_string()),
+                Inline::Italic(vec![Inline::Text("Mice".to_string())]),
+                Inline::Text(" and ".to_string()),
+                Inline::Protected(vec![Inline::Text("Men".to_string())]),
+            ]
+        );
+        assert_eq!(
+            cased("Growth of \\emph{E. coli}", Case::Sentence),
+            "Growth of E. coli"
+        );
+        assert_eq!(
+            cased("growth of \\textbf{E. coli}", Case::Title),
+            "Growth of E. coli"
+        );
+    }
+}
@@ -2,12 +2,16 @@ use std::collections::BTreeMap;
 
 use crate::{
     formaters::Formatter,
-    parsing::names::{and_seperated_names, OwnedFullName},
+    parsing::citation::CitationMode,
+    parsing::date::{Date, EntryDate},
+    parsing::entry::NameLists,
+    parsing::names::{NameKind, OwnedFullName},
 };
-use chrono::prelude::*;
-use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Stylizer, ThesisKind};
+use super::{
+    case::Case, cited_names, editor_label, field, jr, names_of, surname, title, truncate_others,
+    with_notes, Cited, Stylizer, ThesisKind,
+};
 
 #[derive(Default)]
 pub struct IeeeStylizer<T: Formatter> {
@@ -15,99 +19,116 @@ pub struct IeeeStylizer<T: Formatter> {
 }
 
 impl<T: Formatter> Stylizer for IeeeStylizer<T> {
-    fn fmt_book(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_book(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap_or(&String::new()).clone();
-        let publisher = fields.get("publisher").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
-        let year = fields.get("year").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let mut title = title(&self.fmt, fields, Case::Title);
+        let publisher = field(&self.fmt, fields, "publisher");
+        let address = field(&self.fmt, fields, "address");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        let editors = names_of(names, "editor");
+        // edited books without authors are listed under their editors
+        if authors.is_empty() && !editors.is_empty() {
+            out.push_str(&self.fmt_authors(&editors));
+            out.push_str(&format!(", {}, ", editor_label(&editors)));
+        } else {
+            out.push_str(&self.fmt_author_element(authors));
+        }
         self.fmt.italics(&mut title);
         out.push_str(&title);
+        out.push_str(&self.fmt_contributors(names, !authors.is_empty()));
         out.push_str(". ");
         out.push_str(&address);
         out.push_str(": ");
         out.push_str(&publisher);
-        out.push_str(", ");
-        out.push_str(&year);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
         out.push('.');
         out
     }
-    fn fmt_booklet(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_booklet(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap_or(&String::new()).clone();
+        let mut title = title(&self.fmt, fields, Case::Title);
         self.fmt.italics(&mut title);
-        let howpublished = fields.get("howpublished").unwrap_or(&String::new()).clone();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let howpublished = field(&self.fmt, fields, "howpublished");
+        let date = EntryDate::from_fields(fields);
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&title);
         out.push_str(", ");
         out.push_str(&howpublished);
         out.push(',');
-        out.push_str(&self.fmt_year_month(year, month));
+        out.push_str(&self.fmt_date(date.as_ref()));
         out.push('.');
         out
     }
     fn fmt_conference(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap_or(&String::new()).clone();
+        let mut title = title(&self.fmt, fields, Case::Title);
         self.fmt.italics(&mut title);
-        let book_title = fields.get("booktitle").unwrap_or(&String::new()).clone();
-        let publisher = fields.get("publisher").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
-        let organization = fields.get("organization").unwrap_or(&String::new()).clone();
+        let book_title = field(&self.fmt, fields, "booktitle");
+        let publisher = field(&self.fmt, fields, "publisher");
+        let address = field(&self.fmt, fields, "address");
+        let organization = field(&self.fmt, fields, "organization");
         let pages = fields.get("pages").unwrap_or(&String::new()).clone();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let editors_str = fields.get("editor").unwrap_or(&String::new()).clone();
-        let (_tail, edrs) = and_seperated_names(&editors_str).unwrap();
-        let editor_names: Vec<OwnedFullName> = edrs.into_iter().map(|n| n.into()).collect();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let date = EntryDate::from_fields(fields);
+        let editor_names = names_of(names, "editor");
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
         out.push_str(" in ");
         out.push_str(&book_title);
         out.push_str(", ");
-        out.push_str(&self.fmt_authors(editor_names.clone()));
-        out.push_str(", Ed., ");
+        out.push_str(&self.fmt_authors(&editor_names));
+        out.push_str(&format!(", {}, ", editor_label(&editor_names)));
         out.push_str(&organization);
         out.push_str(", ");
         out.push_str(&address);
         out.push_str(": ");
         out.push_str(&publisher);
         out.push(',');
-        out.push_str(&self.fmt_year_month(year, month));
+        out.push_str(&self.fmt_date(date.as_ref()));
         out.push_str(", pp. ");
         out.push_str(&pages);
         out.push('.');
         out
     }
-    fn fmt_inbook(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_inbook(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let book_title = fields.get("booktitle").unwrap_or(&String::new()).clone();
-        let publisher = fields.get("publisher").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
+        let title = title(&self.fmt, fields, Case::Title);
+        let book_title = field(&self.fmt, fields, "booktitle");
+        let publisher = field(&self.fmt, fields, "publisher");
+        let address = field(&self.fmt, fields, "address");
         let pages = fields.get("pages").unwrap_or(&String::new()).clone();
-        let year = fields.get("year").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
         out.push_str(" in ");
         out.push_str(&book_title);
+        out.push_str(&self.fmt_contributors(names, true));
         out.push_str(". ");
         out.push_str(&address);
         out.push_str(": ");
         out.push_str(&publisher);
-        out.push_str(", ");
-        out.push_str(&year);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
         out.push_str(", pp. ");
         out.push_str(&pages);
         out.push('.');
@@ -115,71 +136,75 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
     }
     fn fmt_incollection(
         &self,
-        authors: Vec<OwnedFullName>,
-        mut fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let book_title = fields.get("booktitle").unwrap_or(&String::new()).clone();
-        let publisher = fields.get("publisher").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
+        let title = title(&self.fmt, fields, Case::Title);
+        let book_title = field(&self.fmt, fields, "booktitle");
+        let publisher = field(&self.fmt, fields, "publisher");
+        let address = field(&self.fmt, fields, "address");
         let pages = fields.get("pages").unwrap_or(&String::new()).clone();
-        let year = fields.get("year").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
         out.push_str(" in ");
         out.push_str(&book_title);
         out.push_str(", ");
-        let editors_str = fields.entry("editor".to_string()).or_default();
-        let (_tail, edrs) = and_seperated_names(editors_str).unwrap_or(("", vec![]));
-        let editor_names: Vec<OwnedFullName> = edrs.into_iter().map(|n| n.into()).collect();
-        out.push_str(&self.fmt_authors(editor_names.clone()));
-        out.push_str(", Eds., ");
+        let editor_names = names_of(names, "editor");
+        out.push_str(&self.fmt_authors(&editor_names));
+        out.push_str(&format!(", {}", editor_label(&editor_names)));
+        out.push_str(&self.fmt_contributors(names, false));
+        out.push_str(", ");
         out.push_str(&address);
         out.push_str(": ");
         out.push_str(&publisher);
-        out.push_str(", ");
-        out.push_str(&year);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
         out.push_str(", pp. ");
         out.push_str(&pages);
         out.push('.');
         out
     }
-    fn fmt_manual(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
+    fn fmt_manual(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
         let mut out = String::new();
-        let mut title = fields.get("title").unwrap_or(&String::new()).clone();
+        let mut title = title(&self.fmt, fields, Case::Title);
         self.fmt.italics(&mut title);
-        let organization = fields.get("organization").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
-        let year = fields.get("year").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let organization = field(&self.fmt, fields, "organization");
+        let address = field(&self.fmt, fields, "address");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&title);
         out.push_str(", ");
         out.push_str(&organization);
         out.push_str(", ");
         out.push_str(&address);
-        out.push_str(", ");
-        out.push_str(&year);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
         out.push('.');
         out
     }
     fn fmt_inproceedings(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let book_title = fields.get("booktitle").unwrap_or(&String::new()).clone();
-        let series = fields.get("series").unwrap_or(&String::new()).clone();
-        let publisher = fields.get("publisher").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
+        let title = title(&self.fmt, fields, Case::Title);
+        let book_title = field(&self.fmt, fields, "booktitle");
+        let series = field(&self.fmt, fields, "series");
+        let publisher = field(&self.fmt, fields, "publisher");
+        let address = field(&self.fmt, fields, "address");
         let pages = fields.get("pages").unwrap_or(&String::new()).clone();
-        let year = fields.get("year").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
         out.push_str(" in ");
         out.push_str(&book_title);
@@ -189,28 +214,26 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
         out.push_str(&address);
         out.push_str(": ");
         out.push_str(&publisher);
-        out.push_str(", ");
-        out.push_str(&year);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
         out.push_str(", pp. ");
         out.push_str(&pages);
         out.push('.');
 
         out
     }
-    fn fmt_proceedings(&self, mut fields: BTreeMap<String, String>) -> String {
+    fn fmt_proceedings(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String {
         // J. K. Author, “Title of paper,” presented at the Abbreviated Name of Conf., City of Conf., Abbrev. State, Country, Month and day(s), year, Paper number
         let mut out = String::new();
-        let editors_str = fields.entry("editor".to_string()).or_default();
-        let (_tail, edrs) = and_seperated_names(editors_str).unwrap();
-        let editor_names: Vec<OwnedFullName> = edrs.into_iter().map(|n| n.into()).collect();
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
+        let editor_names = names_of(names, "editor");
+        let title = title(&self.fmt, fields, Case::Title);
         let volume = fields.get("volume").unwrap_or(&String::new()).clone();
-        let series = fields.get("series").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
-        let publisher = fields.get("publisher").unwrap_or(&String::new()).clone();
-        let year = fields.get("year").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(editor_names.clone()));
-        out.push_str(", Eds., ");
+        let series = field(&self.fmt, fields, "series");
+        let address = field(&self.fmt, fields, "address");
+        let publisher = field(&self.fmt, fields, "publisher");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        out.push_str(&self.fmt_authors(&editor_names));
+        out.push_str(&format!(", {}, ", editor_label(&editor_names)));
         out.push_str(&title);
         out.push_str(&format!(", vol. {}, ", volume));
         out.push_str(&series);
@@ -218,20 +241,20 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
         out.push_str(&address);
         out.push_str(": ");
         out.push_str(&publisher);
-        out.push_str(", ");
-        out.push_str(&year);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
 
         out
     }
     fn fmt_unpublished(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
         let mut out = String::new();
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        let title = title(&self.fmt, fields, Case::Title);
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
         out.push_str(" unpublished.");
 
@@ -239,29 +262,33 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
     }
     fn fmt_techreport(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let institution = fields.get("institution").unwrap_or(&String::new()).clone();
-        let address = fields.get("address").unwrap_or(&String::new()).clone();
-
-        let number = fields.get("number").unwrap_or(&String::new()).clone();
-        let year = fields.get("year");
-        let month = fields.get("month");
+        let title = title(&self.fmt, fields, Case::Title);
+        // biblatex `@report`s say what kind of report they are in their `type` field
+        let mut report = fields
+            .get("type")
+            .map(|t| self.fmt.markup(t))
+            .unwrap_or("Tech. Rep.".to_string());
+        if let Some(number) = fields.get("number") {
+            report.push(' ');
+            report.push_str(number);
+        }
+        let date = EntryDate::from_fields(fields);
         let mut out = String::new();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
-        out.push(' ');
-        out.push_str(&institution);
-        out.push_str(", ");
-        out.push_str(&address);
-        out.push_str(", Tech. Rep. ");
-        out.push_str(&number);
-        out.push(',');
-        out.push_str(&self.fmt_year_month(year, month));
-        out.push('.');
+        push_details(
+            &mut out,
+            &[
+                field(&self.fmt, fields, "institution"),
+                field(&self.fmt, fields, "address"),
+                report,
+                self.fmt_date(date.as_ref()),
+            ],
+        );
 
         out
     }
@@ -269,99 +296,106 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
     fn fmt_thesis(
         &self,
         theis_kind: ThesisKind,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String {
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let year = fields.get("year");
-        let month = fields.get("month");
-        let school = fields.get("school");
-        let address = fields.get("address");
+        let title = title(&self.fmt, fields, Case::Title);
+        let date = EntryDate::from_fields(fields);
+        let school = fields
+            .get("school")
+            .or(fields.get("institution"))
+            .map(|v| self.fmt.markup(v))
+            .unwrap_or_default();
+        let kind = match theis_kind {
+            ThesisKind::Phd => "Ph.D. dissertation".to_string(),
+            ThesisKind::Msc => "M.S. thesis".to_string(),
+            ThesisKind::Other(kind) => self.fmt.markup(&kind),
+        };
         let mut out = String::new();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
-        out.push(' ');
-        out.push_str(match theis_kind {
-            ThesisKind::Phd => "Ph.D. dissertation, ",
-            ThesisKind::Msc => "M.S. thesis, ",
-        });
-        if let Some(s) = school {
-            out.push_str(s);
-            out.push(',');
-        }
-        if let Some(a) = address {
-            out.push(' ');
-            out.push_str(a);
-            out.push(',');
-        }
-        out.push_str(&self.fmt_year_month(year, month));
-        out.push('.');
+        push_details(
+            &mut out,
+            &[
+                kind,
+                school,
+                field(&self.fmt, fields, "address"),
+                self.fmt_date(date.as_ref()),
+            ],
+        );
 
         out
     }
 
-    fn fmt_misc(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let year = fields.get("year");
-        let howpublished = fields.get("howpublished");
-        let note = fields.get("note");
+    fn fmt_misc(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let title = title(&self.fmt, fields, Case::Title);
+        let date = EntryDate::from_fields(fields);
+        let howpublished = fields.get("howpublished").map(|v| self.fmt.markup(v));
+        let note = fields.get("note").map(|v| self.fmt.markup(v));
         let mut out = String::new();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&title);
 
         if let Some(u) = howpublished {
             out.push_str(", ");
-            out.push_str(u);
+            out.push_str(&u);
         };
         if let Some(n) = note {
             out.push_str(", ");
-            out.push_str(n);
+            out.push_str(&n);
         };
-        if let Some(y) = year {
-            out.push_str(", ");
-            out.push_str(y);
+        if date.is_some() {
+            out.push(',');
+            out.push_str(&self.fmt_date(date.as_ref()));
             out.push('.');
         };
 
         out
     }
 
-    fn fmt_article(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String {
-        let title = fields.get("title").unwrap_or(&String::new()).clone();
-        let volume = fields.get("volume").unwrap_or(&String::new()).clone();
-        let pages = fields.get("pages");
-        let mut journal = fields.get("journal").unwrap_or(&String::new()).clone();
-        journal.push(',');
-        self.fmt.italics(&mut journal);
-        let number = fields.get("number").unwrap_or(&String::new()).clone();
-        let year = fields.get("year");
-        let month = fields.get("month");
+    fn fmt_article(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let title = title(&self.fmt, fields, Case::Title);
+        let date = EntryDate::from_fields(fields);
         let doi = fields.get("doi");
-        let issn = fields.get("issn");
-        let url = fields.get("url").cloned().map(|mut s| {
-            self.fmt.hyperlink(&mut s);
-            s
-        });
+        let url = fields.get("url");
+        let details: Vec<String> = [
+            fields.get("volume").map(|v| format!("vol. {}", v)),
+            fields.get("number").map(|n| format!("no. {}", n)),
+            fields.get("pages").map(|p| format!("pp. {}", p)),
+            Some(self.fmt_date(date.as_ref()).trim().to_string()),
+            fields.get("issn").map(|i| format!("issn: {}", i)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|d| !d.is_empty())
+        .collect();
+        let mut journal = field(&self.fmt, fields, "journal");
+        if !journal.is_empty() {
+            if !details.is_empty() {
+                journal.push(',');
+            }
+            self.fmt.italics(&mut journal);
+        }
         let mut out = String::new();
-        out.push_str(&self.fmt_authors(authors.clone()));
-        out.push_str(", ");
+        out.push_str(&self.fmt_author_element(authors));
         out.push_str(&fmt_title(title));
-        out.push(' ');
-        out.push_str(&format!("{} vol. {}, no. {},", journal, &volume, &number));
-        if let Some(p) = pages {
-            out.push_str(" pp. ");
-            out.push_str(p);
-            out.push(',');
+        for part in [journal, details.join(", ")] {
+            if !part.is_empty() {
+                out.push(' ');
+                out.push_str(&part);
+            }
         }
-        out.push_str(&self.fmt_year_month(year, month));
-
-        if let Some(i) = issn {
-            out.push(',');
-            out.push_str(" issn: ");
-            out.push_str(i);
-        };
 
         if let Some(d) = doi {
             out.push('.');
@@ -370,53 +404,252 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
             out.push('.');
         };
 
-        if let Some(ref u) = url {
-            out.push_str(" [Online]. Available: ");
-            out.push_str(u);
+        if url.is_some() {
+            out.push_str(&self.available(fields));
             out.push('.');
         };
 
-        if url.is_none() && doi.is_none() && issn.is_none() {
+        if url.is_none() && doi.is_none() {
             out.push('.')
         }
 
         out
     }
 
-    fn fmt_year_month(&self, year: Option<&String>, month: Option<&String>) -> String {
+    fn fmt_online(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let title = title(&self.fmt, fields, Case::Title);
+        let organization = field(&self.fmt, fields, "organization");
+        let date = EntryDate::from_fields(fields);
+        let mut out = String::new();
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&fmt_title(title));
+        if !organization.is_empty() {
+            out.push(' ');
+            out.push_str(&organization);
+            out.push(',');
+        }
+        out.push_str(&self.fmt_date(date.as_ref()));
+        out.push('.');
+        out.push_str(&self.available(fields));
+
+        out
+    }
+
+    fn fmt_software(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let mut title = title(&self.fmt, fields, Case::Title);
+        self.fmt.italics(&mut title);
+        let version = fields.get("version");
+        let publisher = field(&self.fmt, fields, "publisher");
+        let date = EntryDate::from_fields(fields);
+        let mut out = String::new();
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&title);
+        out.push('.');
+        if let Some(v) = version {
+            out.push_str(&format!(" ({}).", v));
+        }
+        if !publisher.is_empty() {
+            out.push(' ');
+            out.push_str(&publisher);
+            out.push(',');
+        }
+        out.push_str(&self.fmt_date(date.as_ref()));
+        out.push('.');
+        out.push_str(&self.available(fields));
+
+        out
+    }
+
+    fn fmt_dataset(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let title = title(&self.fmt, fields, Case::Title);
+        let publisher = field(&self.fmt, fields, "publisher");
+        let date = EntryDate::from_fields(fields);
+        let mut out = String::new();
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&fmt_title(title));
+        if !publisher.is_empty() {
+            out.push(' ');
+            out.push_str(&publisher);
+            out.push(',');
+        }
+        out.push_str(&self.fmt_date(date.as_ref()));
+        out.push('.');
+        if let Some(d) = fields.get("doi") {
+            out.push_str(" doi: ");
+            out.push_str(d);
+            out.push('.');
+        }
+        out.push_str(&self.available(fields));
+
+        out
+    }
+
+    fn fmt_patent(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let title = title(&self.fmt, fields, Case::Title);
+        let kind = fields
+            .get("type")
+            .map(|t| self.fmt.markup(t))
+            .unwrap_or("Patent".to_string());
+        let patent = match fields.get("number") {
+            Some(number) => format!("{} {}", kind, number),
+            None => kind,
+        };
+        let date = EntryDate::from_fields(fields);
         let mut out = String::new();
-        match (year, month) {
-            (None, None) => (),
-            (None, Some(_)) => (),
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&fmt_title(title));
+        push_details(&mut out, &[patent, self.fmt_date(date.as_ref())]);
+
+        out
+    }
+
+    fn fmt_collection(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String {
+        let editor_names = names_of(names, "editor");
+        let mut title = title(&self.fmt, fields, Case::Title);
+        self.fmt.italics(&mut title);
+        let publisher = field(&self.fmt, fields, "publisher");
+        let address = field(&self.fmt, fields, "address");
+        let year = EntryDate::from_fields(fields).map(|d| d.year_only());
+        let mut out = String::new();
+        out.push_str(&self.fmt_authors(&editor_names));
+        out.push_str(&format!(", {}, ", editor_label(&editor_names)));
+        out.push_str(&title);
+        out.push_str(". ");
+        out.push_str(&address);
+        out.push_str(": ");
+        out.push_str(&publisher);
+        out.push(',');
+        out.push_str(&self.fmt_date(year.as_ref()));
+        out.push('.');
+
+        out
+    }
+
+    fn fmt_periodical(&self, _names: &NameLists, fields: &BTreeMap<String, String>) -> String {
+        let mut title = title(&self.fmt, fields, Case::Title);
+        self.fmt.italics(&mut title);
+        let volume = fields.get("volume");
+        let number = fields.get("number");
+        let date = EntryDate::from_fields(fields);
+        let mut out = String::new();
+        out.push_str(&title);
+        out.push(',');
+        if let Some(v) = volume {
+            out.push_str(&format!(" vol. {},", v));
+        }
+        if let Some(n) = number {
+            out.push_str(&format!(" no. {},", n));
+        }
+        out.push_str(&self.fmt_date(date.as_ref()));
+        out.push('.');
+
+        out
+    }
+
+    fn fmt_standard(
+        &self,
+        authors: &[OwnedFullName],
+        _names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String {
+        let mut title = title(&self.fmt, fields, Case::Title);
+        self.fmt.italics(&mut title);
+        let organization = field(&self.fmt, fields, "organization");
+        let number = fields.get("number");
+        let date = EntryDate::from_fields(fields);
+        let mut out = String::new();
+        out.push_str(&self.fmt_author_element(authors));
+        out.push_str(&title);
+        out.push(',');
+        if !organization.is_empty() {
+            out.push(' ');
+            out.push_str(&organization);
+        }
+        if let Some(n) = number {
+            out.push(' ');
+            out.push_str(n);
+        }
+        out.push(',');
+        out.push_str(&self.fmt_date(date.as_ref()));
+        out.push('.');
 
-            (Some(y), None) => {
+        out
+    }
+
+    fn fmt_date(&self, date: Option<&EntryDate>) -> String {
+        let mut out = String::new();
+        match date {
+            None => (),
+            Some(EntryDate::Single { date, circa }) => {
                 out.push(' ');
-                out.push_str(y);
+                if *circa {
+                    out.push_str("c. ");
+                }
+                out.push_str(&fmt_single_date(date));
             }
-            (Some(y), Some(m)) => {
+            Some(EntryDate::Range { start, end, circa }) => {
                 out.push(' ');
-                // years generally don't get represented as anything other than number so unwrapping here is fine
-                let y_parsed = y.parse::<i32>().unwrap();
-                let m_parsed = m.parse::<u32>();
-                let date_formatted = match m_parsed {
-                    Ok(m) => {
-                        let date = NaiveDate::from_ymd_opt(y_parsed, m, 1).unwrap();
-                        date.format("%b").to_string()
+                if *circa {
+                    out.push_str("c. ");
+                }
+                match end {
+                    // e.g. Jan.–Mar. 2019
+                    Some(end) if end.year == start.year && start.month.is_some() => {
+                        out.push_str(&fmt_month_day(start));
+                        out.push('–');
+                        out.push_str(&fmt_single_date(end));
+                    }
+                    Some(end) if end.year == start.year => out.push_str(&end.year.to_string()),
+                    Some(end) => {
+                        out.push_str(&fmt_single_date(start));
+                        out.push('–');
+                        out.push_str(&fmt_single_date(end));
                     }
-                    // if it's not a number just capitalise the first letter
-                    Err(_) => m[0..1].to_uppercase() + &m[1..],
-                };
-                out.push_str(&date_formatted);
-                out.push_str(". ");
-                out.push_str(y);
+                    None => {
+                        out.push_str(&fmt_single_date(start));
+                        out.push_str("–present");
+                    }
+                }
+            }
+            Some(EntryDate::Literal(l)) => {
+                out.push(' ');
+                out.push_str(l);
             }
         };
 
         out
     }
 
-    fn fmt_authors(&self, authors: Vec<OwnedFullName>) -> String {
-        let mut authors = authors.clone();
+    fn fmt_authors(&self, authors: &[OwnedFullName]) -> String {
+        let mut authors = authors.to_vec();
+        if truncate_others(&mut authors) && !authors.is_empty() {
+            let listed = authors
+                .into_iter()
+                .map(fmt_single_author)
+                .collect::<Vec<String>>();
+            let sep = if listed.len() == 1 { " " } else { ", " };
+            return format!("{}{}et al.", listed.join(", "), sep);
+        }
         match &authors.len() {
             0 => String::new(),
             1 => {
@@ -457,33 +690,163 @@ impl<T: Formatter> Stylizer for IeeeStylizer<T> {
             }
         }
     }
+
+    fn fmt_citation(
+        &self,
+        cited: Vec<Cited>,
+        mode: CitationMode,
+        prenote: Option<&str>,
+        postnote: Option<&str>,
+    ) -> String {
+        // the postnote goes with the last number, e.g. "[1], [2, p. 5]"
+        let numbers: Vec<String> = cited
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match i + 1 == cited.len() {
+                true => format!("[{}]", with_notes(c.number.to_string(), None, postnote)),
+                false => format!("[{}]", c.number),
+            })
+            .collect();
+        match mode {
+            CitationMode::Narrative => {
+                let parts: Vec<String> = cited
+                    .iter()
+                    .zip(numbers)
+                    .map(|(c, number)| format!("{} {}", cited_names(c, "and"), number))
+                    .collect();
+                with_notes(parts.join(", "), prenote, None)
+            }
+            CitationMode::Author => {
+                let names: Vec<String> = cited.iter().map(|c| cited_names(c, "and")).collect();
+                with_notes(names.join(", "), prenote, postnote)
+            }
+            CitationMode::Year => {
+                let years: Vec<String> = cited
+                    .iter()
+                    .map(|c| {
+                        let date = EntryDate::from_fields(&c.entry.fields).map(|d| d.year_only());
+                        self.fmt_date(date.as_ref()).trim().to_string()
+                    })
+                    .collect();
+                with_notes(years.join(", "), prenote, postnote)
+            }
+            _ => with_notes(numbers.join(", "), prenote, None),
+        }
+    }
+
+    fn fmt_bibliography(&self, cited: Vec<Cited>) -> Vec<String> {
+        cited
+            .into_iter()
+            .map(|c| format!("[{}] {}", c.number, self.fmt_reference(c.entry)))
+            .collect()
+    }
 }
 
 fn fmt_single_author(name: OwnedFullName) -> String {
+    // organisations are never abbreviated
+    if name.kind == NameKind::Literal {
+        return name.last.join(" ");
+    }
     let mut out = String::new();
     if !name.first.is_empty() {
-        out.push_str(
-            &name
-                .first
-                .iter()
-                .map(|n| format!("{}.", n.graphemes(true).next().unwrap()))
-                .collect::<Vec<String>>()
-                .join(" "),
-        )
+        out.push_str(&name.initials().join(" "))
     };
     if !name.last.is_empty() {
         if !name.first.is_empty() {
             out.push(' ');
         }
-        out.push_str(&name.last.join(" "));
+        out.push_str(&surname(&name));
+    }
+    if let Some(jr) = jr(&name) {
+        out.push_str(", ");
+        out.push_str(&jr);
     }
     out
 }
 
+impl<T: Formatter> IeeeStylizer<T> {
+    /// The authors that start a reference followed by a comma, or nothing for works
+    /// without authors
+    fn fmt_author_element(&self, authors: &[OwnedFullName]) -> String {
+        match authors.is_empty() {
+            true => String::new(),
+            false => format!("{}, ", self.fmt_authors(authors)),
+        }
+    }
+
+    /// e.g. ", Ed. by B. Editor, Trans. by C. Translator" after the title of a work
+    fn fmt_contributors(&self, names: &NameLists, with_editors: bool) -> String {
+        let mut out = String::new();
+        let editors = names_of(names, "editor");
+        if with_editors && !editors.is_empty() {
+            out.push_str(", Ed. by ");
+            out.push_str(&self.fmt_authors(&editors));
+        }
+        let translators = names_of(names, "translator");
+        if !translators.is_empty() {
+            out.push_str(", Trans. by ");
+            out.push_str(&self.fmt_authors(&translators));
+        }
+        out
+    }
+
+    /// the " Accessed: date. [Online]. Available: url" suffix of electronic sources, if they have a url
+    fn available(&self, fields: &BTreeMap<String, String>) -> String {
+        let mut out = String::new();
+        if let Some(u) = fields.get("url") {
+            let mut url = u.clone();
+            self.fmt.hyperlink(&mut url);
+            if let Some(accessed) = EntryDate::urldate(fields) {
+                out.push_str(" Accessed:");
+                out.push_str(&self.fmt_date(Some(&accessed)));
+                out.push('.');
+            }
+            out.push_str(" [Online]. Available: ");
+            out.push_str(&url);
+        }
+        out
+    }
+}
+
+/// e.g. "Jul. 12" or "Jul." without the year
+fn fmt_month_day(date: &Date) -> String {
+    match (date.month, date.day) {
+        (Some(m), Some(d)) => format!("{} {}", m.abbreviation(), d),
+        (Some(m), None) => m.abbreviation().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// e.g. "Jul. 12, 2015", "Jul. 2015" or "2015"
+fn fmt_single_date(date: &Date) -> String {
+    match (date.month, date.day) {
+        (Some(_), Some(_)) => format!("{}, {}", fmt_month_day(date), date.year),
+        (Some(_), None) => format!("{} {}", fmt_month_day(date), date.year),
+        _ => date.year.to_string(),
+    }
+}
+
 fn fmt_title(title: String) -> String {
     format!("\"{},\"", title)
 }
 
+/// add the parts that aren't empty after a title, e.g. " Institution, Tech. Rep. 5, 2001."
+fn push_details(out: &mut String, parts: &[String]) {
+    let parts: Vec<&str> = parts
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !parts.is_empty() {
+        out.push(' ');
+        out.push_str(&parts.join(", "));
+    }
+    // abbreviations like "Tech. Rep." already end in a period
+    if !out.ends_with('.') {
+        out.push('.');
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
@@ -499,9 +862,9 @@ mod test {
     #[test]
     fn random_forests_formatted_citation() -> Result<()> {
         let key = "breiman2001";
-        let formatted_citation = "L. Breiman, \"Random forests,\" Machine learning, vol. 45, no. 1, pp. 5-32, 2001. doi: https://doi.org/10.1023/a:1010933404324.";
+        let formatted_citation = "L. Breiman, \"Random Forests,\" Machine learning, vol. 45, no. 1, pp. 5-32, 2001. doi: https://doi.org/10.1023/a:1010933404324.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -510,9 +873,9 @@ mod test {
     #[test]
     fn bacterial_formatted_citation() -> Result<()> {
         let key = "10.1093/femsec/fiw174";
-        let formatted_citation= "J. Liao, X. Cao, L. Zhao, et al., \"The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists,\" FEMS Microbiology Ecology, vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: https://doi.org/10.1093/femsec/fiw174.";
+        let formatted_citation= "J. Liao, X. Cao, L. Zhao, et al., \"The Importance of Neutral and Niche Processes for Bacterial Community Assembly Differs Between Habitat Generalists and Specialists,\" FEMS Microbiology Ecology, vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: https://doi.org/10.1093/femsec/fiw174.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -521,9 +884,9 @@ mod test {
     #[test]
     fn article_formatted_citation() -> Result<()> {
         let key = "article";
-        let formatted_citation= "P. J. Cohen, \"The independence of the continuum hypothesis,\" Proceedings of the National Academy of Sciences, vol. 50, no. 6, pp. 1143-1148, 1963.";
+        let formatted_citation= "P. J. Cohen, \"The Independence of the Continuum Hypothesis,\" Proceedings of the National Academy of Sciences, vol. 50, no. 6, pp. 1143-1148, 1963.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -532,9 +895,9 @@ mod test {
     #[test]
     fn book_formatted_citation() -> Result<()> {
         let key = "book";
-        let formatted_citation= "L. Susskind and G. Hrabovsky, Classical mechanics: the theoretical minimum. New York, NY: Penguin Random House, 2014.";
+        let formatted_citation= "L. Susskind and G. Hrabovsky, Classical Mechanics: The Theoretical Minimum. New York, NY: Penguin Random House, 2014.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -543,9 +906,9 @@ mod test {
     #[test]
     fn booklet_formatted_citation() -> Result<()> {
         let key = "booklet";
-        let formatted_citation= "M. Swetla, Canoe tours in Sweden, Distributed at the Stockholm Tourist Office, Jul. 2015.";
+        let formatted_citation= "M. Swetla, Canoe Tours in Sweden, Distributed at the Stockholm Tourist Office, Jul. 2015.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -556,7 +919,7 @@ mod test {
         let key = "inbook";
         let formatted_citation= "L. A. Urry, M. L. Cain, S. A. Wasserman, P. V. Minorsky, and J. B. Reece, \"Photosynthesis,\" in Campbell biology. New York, NY: Pearson, 2016, pp. 187-221.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -565,9 +928,9 @@ mod test {
     #[test]
     fn incollection_formatted_citation() -> Result<()> {
         let key = "incollection";
-        let formatted_citation= "H. M. Shapiro, \"Flow cytometry: The glass is half full,\" in Flow cytometry protocols, T. S. Hawley and R. G. Hawley, Eds., New York, NY: Springer, 2018, pp. 1-10.";
+        let formatted_citation= "H. M. Shapiro, \"Flow Cytometry: The Glass Is Half Full,\" in Flow cytometry protocols, T. S. Hawley and R. G. Hawley, Eds., New York, NY: Springer, 2018, pp. 1-10.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -576,9 +939,9 @@ mod test {
     #[test]
     fn inprocedings_formatted_citation() -> Result<()> {
         let key = "inproceedings";
-        let formatted_citation= "P. Holleis, M. Wagner, and J. Koolwaaij, \"Studying mobile context-aware social services in the wild,\" in Proc. of the 6th Nordic Conf. on Human-Computer Interaction, ser. NordiCHI, New York, NY: ACM, 2010, pp. 207-216.";
+        let formatted_citation= "P. Holleis, M. Wagner, and J. Koolwaaij, \"Studying Mobile Context-Aware Social Services in the Wild,\" in Proc. of the 6th Nordic Conf. on Human-Computer Interaction, ser. NordiCHI, New York, NY: ACM, 2010, pp. 207-216.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -587,9 +950,9 @@ mod test {
     #[test]
     fn manual_formatted_citation() -> Result<()> {
         let key = "manual";
-        let formatted_citation= "R Core Team, R: A language and environment for statistical computing, R Foundation for Statistical Computing, Vienna, Austria, 2018.";
+        let formatted_citation= "R Core Team, R: A Language and Environment for Statistical Computing, R Foundation for Statistical Computing, Vienna, Austria, 2018.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -598,9 +961,9 @@ mod test {
     #[test]
     fn manual_formatted_citation_html() -> Result<()> {
         let key = "manual";
-        let formatted_citation= "R Core Team, <i>R: A language and environment for statistical computing</i>, R Foundation for Statistical Computing, Vienna, Austria, 2018.";
+        let formatted_citation= "R Core Team, <i>R: A Language and Environment for Statistical Computing</i>, R Foundation for Statistical Computing, Vienna, Austria, 2018.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<HtmlFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -609,9 +972,9 @@ mod test {
     #[test]
     fn mastersthesis_formatted_citation() -> Result<()> {
         let key = "mastersthesis";
-        let formatted_citation= "J. Tang, \"Spin structure of the nucleon in the asymptotic limit,\" M.S. thesis, Massachusetts Institute of Technology, Cambridge, MA, Sep. 1996.";
+        let formatted_citation= "J. Tang, \"Spin Structure of the Nucleon in the Asymptotic Limit,\" M.S. thesis, Massachusetts Institute of Technology, Cambridge, MA, Sep. 1996.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -620,9 +983,9 @@ mod test {
     #[test]
     fn misc_formatted_citation() -> Result<()> {
         let key = "misc";
-        let formatted_citation= "NASA, Pluto: The 'other' red planet, https://www.nasa.gov/nh/pluto-the-other-red-planet, Accessed: 2018-12-06, 2015.";
+        let formatted_citation= "NASA, Pluto: The 'Other' Red Planet, https://www.nasa.gov/nh/pluto-the-other-red-planet, Accessed: 2018-12-06, 2015.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -631,9 +994,9 @@ mod test {
     #[test]
     fn phdthesis_formatted_citation() -> Result<()> {
         let key = "phdthesis";
-        let formatted_citation= "R. C. Rempel, \"Relaxation effects for coupled nuclear spins,\" Ph.D. dissertation, Stanford University, Stanford, CA, Jun. 1956.";
+        let formatted_citation= "R. C. Rempel, \"Relaxation Effects for Coupled Nuclear Spins,\" Ph.D. dissertation, Stanford University, Stanford, CA, Jun. 1956.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -642,9 +1005,9 @@ mod test {
     #[test]
     fn proceedings_formatted_citation() -> Result<()> {
         let key = "proceedings";
-        let formatted_citation= "S. Stepney and S. Verlan, Eds., Proceedings of the 17th international conference on computation and natural computation, fontainebleau, france, vol. 10867, Lecture Notes in Computer Science, Cham, Switzerland: Springer, 2018";
+        let formatted_citation= "S. Stepney and S. Verlan, Eds., Proceedings of the 17th International Conference on Computation and Natural Computation, Fontainebleau, France, vol. 10867, Lecture Notes in Computer Science, Cham, Switzerland: Springer, 2018";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -653,9 +1016,9 @@ mod test {
     #[test]
     fn techreport_formatted_citation() -> Result<()> {
         let key = "techreport";
-        let formatted_citation= "V. Bennett, K. Bowman, and S. Wright, \"Wasatch Solar Project final report,\" Salt Lake City Corporation, Salt Lake City, UT, Tech. Rep. DOE-SLC-6903-1, Sep. 2018.";
+        let formatted_citation= "V. Bennett, K. Bowman, and S. Wright, \"Wasatch Solar Project Final Report,\" Salt Lake City Corporation, Salt Lake City, UT, Tech. Rep. DOE-SLC-6903-1, Sep. 2018.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -666,7 +1029,7 @@ mod test {
         let key = "conference";
         let formatted_citation= "J. Smith and J. Doe, \"The Effects of Climate Change,\" in Proceedings of the Annual Conference on Climate Change, B. Johnson, Ed., Climate Change Association, Los Angeles, CA: Springer, Jun. 2022, pp. 55-62.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -675,9 +1038,9 @@ mod test {
     #[test]
     fn unpublished_formatted_citation() -> Result<()> {
         let key = "unpublished";
-        let formatted_citation = "M. Suresh, \"Evolution: A revised theory,\" unpublished.";
+        let formatted_citation = "M. Suresh, \"Evolution: A Revised Theory,\" unpublished.";
         let entries = Bibliography::from_file(PathBuf::from("cite.bib"))?;
-        let entry = entries.get_entry(key.to_string()).unwrap();
+        let entry = entries.get_entry(key).unwrap();
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
         let citation = stylizer.fmt_reference(entry);
         assert_eq!(citation, formatted_citation);
@@ -689,10 +1052,13 @@ mod test {
             first: vec!["Ada".to_string(), "Maria".to_string()],
             last: vec!["Lovelace".to_string(), "Augusta".to_string()],
             von: vec![],
+            jr: vec![],
+            kind: NameKind::Person,
+            langid: None,
             title: vec![],
         };
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(vec![author]);
+        let formated = stylizer.fmt_authors(&[author]);
         assert_eq!(formated, "A. M. Lovelace Augusta");
 
         Ok(())
@@ -704,17 +1070,23 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(formated, "A. M. Lovelace Augusta and A. E. Noether");
 
         Ok(())
@@ -726,23 +1098,32 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(
             formated,
             "A. M. Lovelace Augusta, A. E. Noether, and S. Germain"
@@ -757,41 +1138,59 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sofia".to_string()],
                 last: vec!["Kovalevskaya".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Dorothy".to_string()],
                 last: vec!["Vaughn".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Maryam".to_string()],
                 last: vec!["Mirzakhani".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(
             formated,
             "A. M. Lovelace Augusta, A. E. Noether, S. Germain, S. Kovalevskaya, D. Vaughn, and M. Mirzakhani"
@@ -806,59 +1205,86 @@ mod test {
                 first: vec!["Ada".to_string(), "Maria".to_string()],
                 last: vec!["Lovelace".to_string(), "Augusta".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Amalie".to_string(), "Emmy".to_string()],
                 last: vec!["Noether".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sophie".to_string()],
                 last: vec!["Germain".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Sofia".to_string()],
                 last: vec!["Kovalevskaya".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Dorothy".to_string()],
                 last: vec!["Vaughn".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Maryam".to_string()],
                 last: vec!["Mirzakhani".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Mae".to_string(), "Carol".to_string()],
                 last: vec!["Jemison".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Julia".to_string()],
                 last: vec!["Robinson".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
             OwnedFullName {
                 first: vec!["Katherine".to_string()],
                 last: vec!["Johnson".to_string()],
                 von: vec![],
+                jr: vec![],
+                kind: NameKind::Person,
+                langid: None,
                 title: vec![],
             },
         ];
         let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
-        let formated = stylizer.fmt_authors(authors);
+        let formated = stylizer.fmt_authors(&authors);
         assert_eq!(
             formated,
             "A. M. Lovelace Augusta, A. E. Noether, S. Germain, et al."
@@ -866,4 +1292,329 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn latex_formatting_in_title() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@article{coli,
+                author = {Jane Doe},
+                title = {Growth of \emph{{E. coli}} at $37^\circ$C},
+                journal = {Journal of \textbf{Bacteria}},
+                volume = 1,
+                number = 2,
+                year = 2020,
+                url = {https://example.com},
+            }",
+        )?;
+        let entry = bib.get_entry("coli").unwrap();
+        let stylizer = IeeeStylizer::<HtmlFormatter>::default();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "J. Doe, \"Growth of <i>E. coli</i> at <i>37^\\circ</i>C,\" <i>Journal of <b>Bacteria</b>,</i> vol. 1, no. 2, 2020 [Online]. Available: <a href=\"https://example.com\">https://example.com</a>."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minimal_report() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@report{minimal,
+                author = {Jane Doe},
+                title = {Widgets},
+                institution = {ACME},
+                year = 2020,
+            }
+            @report{typed,
+                author = {Jane Doe},
+                title = {Widgets},
+                type = {White paper},
+                number = 7,
+                institution = {ACME},
+                address = {Springfield},
+                year = 2020,
+            }",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("minimal").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "J. Doe, \"Widgets,\" ACME, Tech. Rep., 2020."
+        );
+        let entry = bib.get_entry("typed").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "J. Doe, \"Widgets,\" ACME, Springfield, White paper 7, 2020."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn von_part_is_printed() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@book{symphony,
+                author = {Ludwig van Beethoven and O'Neil, Cathy},
+                title = {Symphony No. 9},
+                publisher = {Schott},
+                address = {Mainz},
+                year = 1826,
+            }",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("symphony").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "L. van Beethoven and C. O'Neil, Symphony No. 9. Mainz: Schott, 1826."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn biblatex_entry_types() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@online{site,
+                author = {Jane Doe},
+                title = {the {cite-me-bro} homepage},
+                organization = {GitHub},
+                year = 2023,
+                url = {https://github.com/savente93/cite-me-bro},
+            }
+            @software{tool,
+                author = {Jane Doe},
+                title = {{cite-me-bro}},
+                version = {0.1.0},
+                publisher = {Zenodo},
+                year = 2023,
+            }
+            @thesis{thesis,
+                author = {Jane Doe},
+                title = {on citations},
+                type = {phdthesis},
+                institution = {Delft University of Technology},
+                year = 2019,
+            }
+            @thesis{bachelor,
+                author = {Jane Doe},
+                title = {on references},
+                type = {Bachelor's thesis},
+                school = {Delft University of Technology},
+                year = 2015,
+            }
+            @patent{patent,
+                author = {Jane Doe},
+                title = {a citation machine},
+                type = {U.S. Patent},
+                number = {1234567},
+                year = 2020,
+            }
+            @artwork{other,
+                author = {Jane Doe},
+                title = {a painting},
+                year = 2021,
+            }",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(
+            formatted("site"),
+            "J. Doe, \"The cite-me-bro Homepage,\" GitHub, 2023. [Online]. Available: https://github.com/savente93/cite-me-bro"
+        );
+        assert_eq!(
+            formatted("tool"),
+            "J. Doe, cite-me-bro. (0.1.0). Zenodo, 2023."
+        );
+        assert_eq!(
+            formatted("thesis"),
+            "J. Doe, \"On Citations,\" Ph.D. dissertation, Delft University of Technology, 2019."
+        );
+        assert_eq!(
+            formatted("bachelor"),
+            "J. Doe, \"On References,\" Bachelor's thesis, Delft University of Technology, 2015."
+        );
+        assert_eq!(
+            formatted("patent"),
+            "J. Doe, \"A Citation Machine,\" U.S. Patent 1234567, 2020."
+        );
+        assert_eq!(formatted("other"), "J. Doe, A Painting, 2021.");
+        Ok(())
+    }
+
+    #[test]
+    fn dates() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@misc{range,
+                author = {Jane Doe},
+                title = {Range},
+                date = {2019-01/2019-03},
+            }
+            @misc{years,
+                author = {Jane Doe},
+                title = {Years},
+                date = {2019/},
+            }
+            @misc{day,
+                author = {Jane Doe},
+                title = {Day},
+                date = {2020-05-12},
+            }
+            @misc{circa,
+                author = {Jane Doe},
+                title = {Circa},
+                date = {1850~},
+            }
+            @misc{suffix,
+                author = {Jane Doe},
+                title = {Suffix},
+                year = {2021a},
+                month = 5,
+            }
+            @misc{forthcoming,
+                author = {Jane Doe},
+                title = {Forthcoming},
+                year = {forthcoming},
+                month = jan,
+            }
+            @online{accessed,
+                author = {Jane Doe},
+                title = {Accessed},
+                year = 2020,
+                url = {https://example.com},
+                urldate = {2023-10-01},
+            }",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(formatted("range"), "J. Doe, Range, Jan.–Mar. 2019.");
+        assert_eq!(formatted("years"), "J. Doe, Years, 2019–present.");
+        assert_eq!(formatted("day"), "J. Doe, Day, May 12, 2020.");
+        assert_eq!(formatted("circa"), "J. Doe, Circa, c. 1850.");
+        assert_eq!(formatted("suffix"), "J. Doe, Suffix, May 2021a.");
+        assert_eq!(
+            formatted("forthcoming"),
+            "J. Doe, Forthcoming, forthcoming."
+        );
+        assert_eq!(formatted("accessed"), "J. Doe, \"Accessed,\" 2020. Accessed: Oct. 1, 2023. [Online]. Available: https://example.com");
+        Ok(())
+    }
+
+    #[test]
+    fn month_representations() -> Result<()> {
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        for month in ["jan", "{jan}", "{January}", "1", "{01}", "{Jan.}"] {
+            let bib = Bibliography::from_bib_str(&format!(
+                "@misc{{foo, author = {{Jane Doe}}, title = {{Foo}}, year = 2020, month = {}}}",
+                month
+            ))?;
+            let entry = bib.get_entry("foo").unwrap();
+            assert_eq!(stylizer.fmt_reference(entry), "J. Doe, Foo, Jan. 2020.");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn editors_and_translators() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@book{edited,
+                editor = {Jane Doe and John Smith},
+                title = {An edited book},
+                address = {Berlin},
+                publisher = {Springer},
+                year = 2020,
+            }
+            @book{translated,
+                author = {Leo Tolstoy},
+                editor = {Jane Doe},
+                translator = {Louise Maude and Aylmer Maude},
+                title = {War and peace},
+                address = {Oxford},
+                publisher = {Oxford University Press},
+                year = 2010,
+            }",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(
+            formatted("edited"),
+            "J. Doe and J. Smith, Eds., An Edited Book. Berlin: Springer, 2020."
+        );
+        assert_eq!(
+            formatted("translated"),
+            "L. Tolstoy, War and Peace, Ed. by J. Doe, Trans. by L. Maude and A. Maude. Oxford: Oxford University Press, 2010."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn jr_name_parts() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo, author = {Doe, Jr., John and Smith, III, Jane}, title = {Foo}, year = 2020}",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "J. Doe, Jr. and J. Smith, III, Foo, 2020."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn organisations_as_authors() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo, author = {{World Health Organization} and Jane Doe}, title = {Foo}, year = 2020}",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "World Health Organization and J. Doe, Foo, 2020."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn and_others() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{one, author = {Jane Doe and others}, title = {Foo}, year = 2020}
+            @misc{two, author = {Jane Doe and John Smith and others}, title = {Foo}, year = 2020}",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(formatted("one"), "J. Doe et al., Foo, 2020.");
+        assert_eq!(formatted("two"), "J. Doe, J. Smith, et al., Foo, 2020.");
+        Ok(())
+    }
+
+    #[test]
+    fn compound_initials() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            "@misc{foo, author = {Jean-Paul Sartre and Christian Chalupa}, title = {Foo}, year = 2020, langid = {czech}}",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let entry = bib.get_entry("foo").unwrap();
+        assert_eq!(
+            stylizer.fmt_reference(entry),
+            "J.-P. Sartre and Ch. Chalupa, Foo, 2020."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_fields_leave_no_punctuation() -> Result<()> {
+        let bib = Bibliography::from_bib_str(
+            r"@report{untitled, title = {Untitled Report}}
+            @article{bare, author = {Jane Doe}, title = {Widgets}, journal = {Gadgets}, year = 2020}
+            @patent{pending, author = {Jane Doe}, title = {Widgets}, year = 2020}
+            @thesis{thesis, author = {Jane Doe}, title = {Widgets}, type = {phdthesis}}",
+        )?;
+        let stylizer = IeeeStylizer::<PlainTextFormatter>::default();
+        let formatted = |key: &str| stylizer.fmt_reference(bib.get_entry(key).unwrap());
+        assert_eq!(formatted("untitled"), "\"Untitled Report,\" Tech. Rep.");
+        assert_eq!(formatted("bare"), "J. Doe, \"Widgets,\" Gadgets, 2020.");
+        assert_eq!(formatted("pending"), "J. Doe, \"Widgets,\" Patent, 2020.");
+        assert_eq!(
+            formatted("thesis"),
+            "J. Doe, \"Widgets,\" Ph.D. dissertation."
+        );
+        Ok(())
+    }
 }
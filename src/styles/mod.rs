@@ -1,19 +1,26 @@
 use anyhow::anyhow;
-use std::collections::BTreeMap;
+use std::{borrow::Cow, collections::BTreeMap};
 
 use apa::ApaStylizer;
+use case::{change_case, Case};
 use ieee::IeeeStylizer;
 
 use crate::{
-    formaters::{html::HtmlFormatter, markdown::MarkdownFormatter, plain::PlainTextFormatter},
+    formaters::{
+        html::HtmlFormatter, markdown::MarkdownFormatter, plain::PlainTextFormatter, Formatter,
+    },
     parsing::{
-        entry::{BibEntry, EntryType},
-        names::OwnedFullName,
+        citation::CitationMode,
+        date::EntryDate,
+        entry::{BibEntry, EntryType, NameLists},
+        markup::{parse_markup, plain_text},
+        names::{NameKind, OwnedFullName, ARTICLES},
     },
     Format,
 };
 
 pub mod apa;
+pub mod case;
 pub mod ieee;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -35,13 +42,445 @@ impl TryFrom<&str> for ReferenceStyle {
     }
 }
 
+/// The order of a reference list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortOrder {
+    /// the order the style prescribes: by author, year and title for APA and in the
+    /// order they are cited for IEEE
+    #[default]
+    Style,
+    /// by citation key
+    Key,
+    /// by year, then by author and title
+    Year,
+    /// in the order they are cited, or the order of the bib file
+    None,
+}
+
+impl TryFrom<&str> for SortOrder {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "style" => Ok(SortOrder::Style),
+            "key" => Ok(SortOrder::Key),
+            "year" => Ok(SortOrder::Year),
+            "none" => Ok(SortOrder::None),
+            _ => Err(anyhow!("invalid sort order")),
+        }
+    }
+}
+
+/// the names in one of the name list fields, e.g. the editors
+fn names_of(names: &NameLists, field: &str) -> Vec<OwnedFullName> {
+    names.get(field).cloned().unwrap_or_default()
+}
+
+/// Remove the `others` of `and others` from a list of names, returning whether the list
+/// was truncated so the style can add "et al."
+fn truncate_others(names: &mut Vec<OwnedFullName>) -> bool {
+    let truncated = names.iter().any(|n| n.kind == NameKind::Others);
+    names.retain(|n| n.kind != NameKind::Others);
+    truncated
+}
+
+/// How much of the given names of the first author a citation shows, to tell them apart
+/// from other cited authors with the same surname
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GivenNames {
+    /// only the surname, e.g. "Wang"
+    #[default]
+    None,
+    /// the initials, e.g. "J. Wang"
+    Initials,
+    /// the given names in full, e.g. "Jie Wang"
+    Full,
+}
+
+/// An entry cited in the text
+#[derive(Clone)]
+pub struct Cited<'a> {
+    pub entry: &'a BibEntry,
+    /// the position of the entry in a bibliography numbered by first citation
+    pub number: usize,
+    /// the letter after the year that sets the entry apart from others by the same
+    /// authors in the same year, e.g. the "a" of "2001a"
+    pub year_suffix: Option<String>,
+    pub given_names: GivenNames,
+}
+
+impl<'a> Cited<'a> {
+    pub fn new(entry: &'a BibEntry, number: usize) -> Self {
+        Self {
+            entry,
+            number,
+            year_suffix: None,
+            given_names: GivenNames::None,
+        }
+    }
+
+    /// The entry with the letter after its year passed on as `extradate`, like biblatex does
+    pub fn dated_entry(&self) -> Cow<'a, BibEntry> {
+        match &self.year_suffix {
+            Some(suffix) => {
+                let mut entry = self.entry.clone();
+                entry.fields.insert("extradate".to_string(), suffix.clone());
+                Cow::Owned(entry)
+            }
+            None => Cow::Borrowed(self.entry),
+        }
+    }
+}
+
+/// the names an entry is cited by: its authors, or its editors if it has none
+fn citation_names(entry: &BibEntry) -> Vec<OwnedFullName> {
+    if entry.authors.is_empty() {
+        names_of(&entry.names, "editor")
+    } else {
+        entry.authors.clone()
+    }
+}
+
+/// the surname of a name including its von part, e.g. "van Beethoven"
+fn surname(name: &OwnedFullName) -> String {
+    match name.kind {
+        NameKind::Person => name
+            .von
+            .iter()
+            .chain(&name.last)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => name.last.join(" "),
+    }
+}
+
+/// Who a cited entry is by as it's named in the text, e.g. "Doe", "Doe and Smith" or
+/// "Doe et al.". Entries without authors or editors are named by their title.
+fn cited_names(cited: &Cited, and: &str) -> String {
+    let entry = &cited.entry;
+    let mut names = citation_names(entry);
+    let truncated = truncate_others(&mut names);
+    let mut names: Vec<String> = names
+        .iter()
+        .enumerate()
+        .map(|(i, n)| {
+            let given = match (i, cited.given_names, n.kind) {
+                (0, GivenNames::Initials, NameKind::Person) => n.initials().join(" "),
+                (0, GivenNames::Full, NameKind::Person) => n.first.join(" "),
+                _ => String::new(),
+            };
+            match given.is_empty() {
+                true => surname(n),
+                false => format!("{} {}", given, surname(n)),
+            }
+        })
+        .collect();
+    match (names.len(), truncated) {
+        (0, _) => entry
+            .fields
+            .get("title")
+            .cloned()
+            .unwrap_or_else(|| entry.key.clone()),
+        (1, false) => names.remove(0),
+        (2, false) => format!("{} {} {}", names[0], and, names[1]),
+        _ => format!("{} et al.", names[0]),
+    }
+}
+
+/// Set apart cited entries that would look the same in an author–year citation. First
+/// authors with the same surname get their initials, or their given names if those are
+/// the same as well. Entries by the same authors in the same year get a letter after the
+/// year, in the order of the reference list.
+fn disambiguate(cited: &mut [Cited]) {
+    let first_authors: Vec<Option<OwnedFullName>> = cited
+        .iter()
+        .map(|c| {
+            let mut names = citation_names(c.entry);
+            truncate_others(&mut names);
+            names.into_iter().next()
+        })
+        .collect();
+    // the different people with each surname
+    let mut people: BTreeMap<String, Vec<&OwnedFullName>> = BTreeMap::new();
+    for name in first_authors.iter().flatten() {
+        let same_surname = people.entry(surname(name).to_lowercase()).or_default();
+        if !same_surname.iter().any(|n| n.sort_key() == name.sort_key()) {
+            same_surname.push(name);
+        }
+    }
+    for (c, name) in cited.iter_mut().zip(&first_authors) {
+        let name = match name {
+            Some(n) if n.kind == NameKind::Person => n,
+            _ => continue,
+        };
+        let same_surname = &people[&surname(name).to_lowercase()];
+        if same_surname.len() < 2 {
+            continue;
+        }
+        let initials = name.initials();
+        let same_initials = same_surname
+            .iter()
+            .filter(|n| n.initials() == initials)
+            .count();
+        c.given_names = match same_initials {
+            1 => GivenNames::Initials,
+            _ => GivenNames::Full,
+        };
+    }
+
+    let keys: Vec<_> = cited.iter().map(|c| author_year_title(c.entry)).collect();
+    // people with the same initials are told apart by their given names already, and
+    // entries without authors are cited by their title
+    let authors: Vec<Vec<String>> = cited
+        .iter()
+        .zip(&keys)
+        .map(|(c, (title_or_names, _, _))| {
+            let mut names = citation_names(c.entry);
+            truncate_others(&mut names);
+            match names.is_empty() {
+                true => title_or_names.clone(),
+                false => names.iter().map(|n| n.sort_key()).collect(),
+            }
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..cited.len()).collect();
+    order.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+    let mut same_year: BTreeMap<(&Vec<String>, Option<i32>), Vec<usize>> = BTreeMap::new();
+    for i in order {
+        let (_, (_, year), _) = &keys[i];
+        same_year.entry((&authors[i], *year)).or_default().push(i);
+    }
+    for group in same_year.values().filter(|g| g.len() > 1) {
+        for (n, i) in group.iter().enumerate() {
+            cited[*i].year_suffix = Some(year_suffix(n));
+        }
+    }
+}
+
+/// "a" to "z", then "aa", "ab" and so on
+fn year_suffix(n: usize) -> String {
+    let letter = |i: usize| char::from(b'a' + (i % 26) as u8);
+    match n < 26 {
+        true => letter(n).to_string(),
+        false => format!("{}{}", letter(n / 26 - 1), letter(n)),
+    }
+}
+
+/// Text as it's compared when sorting: lowercase, without braces and without a leading
+/// article, so "The {DNA} of {AI}" sorts as "dna of ai"
+fn sortable(text: &str) -> String {
+    let text = text.replace(['{', '}'], "").to_lowercase();
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() > 1 && ARTICLES.contains(&words[0]) {
+        words.remove(0);
+    }
+    words.join(" ")
+}
+
+/// How a name is sorted in an author–year reference list: by the surname and then the
+/// initials, so people with the same initials are sorted by year
+fn name_sort_key(name: &OwnedFullName) -> String {
+    match name.kind {
+        NameKind::Person => [
+            name.last.join(" "),
+            name.initials().join(" "),
+            name.jr.join(" "),
+        ]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase(),
+        _ => name.sort_key(),
+    }
+}
+
+/// The position of an entry in an author–year reference list: by the names of the
+/// authors (or editors), then the year and then the title as it's printed. Entries without
+/// either take the place of their title, and entries without a year come after those with one.
+fn author_year_title(entry: &BibEntry) -> (Vec<String>, (bool, Option<i32>), String) {
+    let mut names = citation_names(entry);
+    truncate_others(&mut names);
+    let title = entry
+        .fields
+        .get("title")
+        .map(|t| sortable(&plain_text(&parse_markup(t))))
+        .unwrap_or_default();
+    let names = match names.is_empty() {
+        true => vec![title.clone()],
+        false => names.iter().map(name_sort_key).collect(),
+    };
+    let year = EntryDate::from_fields(&entry.fields).and_then(|d| d.year());
+    (names, (year.is_none(), year), title)
+}
+
+/// Put the pre- and postnote of a citation around it, e.g. "see Doe, 2020, p. 5"
+fn with_notes(text: String, prenote: Option<&str>, postnote: Option<&str>) -> String {
+    let mut out = String::new();
+    if let Some(prenote) = prenote {
+        out.push_str(prenote);
+        out.push(' ');
+    }
+    out.push_str(&text);
+    if let Some(postnote) = postnote {
+        out.push_str(", ");
+        out.push_str(postnote);
+    }
+    out
+}
+
+/// the jr part of a name, e.g. "Jr." or "III", if it has one
+fn jr(name: &OwnedFullName) -> Option<String> {
+    if name.jr.is_empty() {
+        return None;
+    }
+    let jr = name.jr.join(" ");
+    // abbreviations like "Jr" lose their period when parsed, numerals like "III" never had one
+    match jr.chars().last() {
+        Some(c) if c.is_lowercase() => Some(format!("{}.", jr)),
+        _ => Some(jr),
+    }
+}
+
+/// "Ed." for a single editor, "Eds." for more
+fn editor_label(editors: &[OwnedFullName]) -> &'static str {
+    if editors.len() == 1 {
+        "Ed."
+    } else {
+        "Eds."
+    }
+}
+
+/// the value of a field with its formatting rendered, or an empty string if it isn't there
+fn field<T: Formatter>(fmt: &T, fields: &BTreeMap<String, String>, name: &str) -> String {
+    fields
+        .get(name)
+        .map(|value| fmt.markup(value))
+        .unwrap_or_default()
+}
+
+/// the rendered title of an entry in the case the style prescribes
+fn title<T: Formatter>(fmt: &T, fields: &BTreeMap<String, String>, case: Case) -> String {
+    fields
+        .get("title")
+        .map(|title| fmt.render(&change_case(&parse_markup(title), case)))
+        .unwrap_or_default()
+}
+
 pub enum ThesisKind {
     Phd,
     Msc,
+    /// any other kind of thesis, as written in the `type` field
+    Other(String),
+}
+
+impl ThesisKind {
+    /// the kind of a biblatex `@thesis`, from it's `type` field
+    pub fn from_type(kind: Option<&String>) -> Self {
+        match kind.map(|k| k.to_lowercase()).as_deref() {
+            Some("phdthesis") | Some("phd") | Some("doctoral dissertation") => ThesisKind::Phd,
+            Some("mathesis") | Some("mastersthesis") | Some("msc") | Some("master's thesis") => {
+                ThesisKind::Msc
+            }
+            Some(_) => ThesisKind::Other(kind.cloned().unwrap_or_default()),
+            None => ThesisKind::Other("Thesis".to_string()),
+        }
+    }
 }
 
 impl ReferenceStyle {
-    pub fn fmt_reference(&self, entry: BibEntry, format: Format) -> String {
+    /// The in text form of a citation, for any mode but [`CitationMode::Full`] and
+    /// [`CitationMode::NoCite`] which aren't up to the style.
+    pub fn fmt_citation(
+        &self,
+        cited: Vec<Cited>,
+        mode: CitationMode,
+        prenote: Option<&str>,
+        postnote: Option<&str>,
+        format: Format,
+    ) -> String {
+        match (self, format) {
+            (ReferenceStyle::IEEE, Format::Plain) => IeeeStylizer::<PlainTextFormatter>::default()
+                .fmt_citation(cited, mode, prenote, postnote),
+            (ReferenceStyle::IEEE, Format::Html) => IeeeStylizer::<HtmlFormatter>::default()
+                .fmt_citation(cited, mode, prenote, postnote),
+            (ReferenceStyle::IEEE, Format::Markdown) => {
+                IeeeStylizer::<MarkdownFormatter>::default()
+                    .fmt_citation(cited, mode, prenote, postnote)
+            }
+            (ReferenceStyle::APA, Format::Plain) => ApaStylizer::<PlainTextFormatter>::default()
+                .fmt_citation(cited, mode, prenote, postnote),
+            (ReferenceStyle::APA, Format::Html) => {
+                ApaStylizer::<HtmlFormatter>::default().fmt_citation(cited, mode, prenote, postnote)
+            }
+            (ReferenceStyle::APA, Format::Markdown) => ApaStylizer::<MarkdownFormatter>::default()
+                .fmt_citation(cited, mode, prenote, postnote),
+        }
+    }
+
+    /// Sort the entries of a reference list. Their numbers are the order they are
+    /// given in, which is kept for [`SortOrder::None`].
+    pub fn sort(&self, cited: &mut [Cited], order: SortOrder) {
+        match (order, self) {
+            (SortOrder::Style, ReferenceStyle::APA) => {
+                cited.sort_by_cached_key(|c| author_year_title(c.entry))
+            }
+            (SortOrder::Style, ReferenceStyle::IEEE) | (SortOrder::None, _) => {
+                cited.sort_by_key(|c| c.number)
+            }
+            (SortOrder::Key, _) => cited.sort_by_cached_key(|c| c.entry.key.to_lowercase()),
+            (SortOrder::Year, _) => cited.sort_by_cached_key(|c| {
+                let (names, year, title) = author_year_title(c.entry);
+                (year, names, title)
+            }),
+        }
+    }
+
+    /// Set apart cited entries that would otherwise look the same in the text, which
+    /// only author–year styles have to do
+    pub fn disambiguate(&self, cited: &mut [Cited]) {
+        match self {
+            ReferenceStyle::IEEE => (),
+            ReferenceStyle::APA => disambiguate(cited),
+        }
+    }
+
+    /// The reference list of the cited entries, see [`Stylizer::fmt_bibliography`]
+    pub fn fmt_bibliography(&self, cited: Vec<Cited>, format: Format) -> Vec<String> {
+        match (self, format) {
+            (ReferenceStyle::IEEE, Format::Plain) => {
+                IeeeStylizer::<PlainTextFormatter>::default().fmt_bibliography(cited)
+            }
+            (ReferenceStyle::IEEE, Format::Html) => {
+                IeeeStylizer::<HtmlFormatter>::default().fmt_bibliography(cited)
+            }
+            (ReferenceStyle::IEEE, Format::Markdown) => {
+                IeeeStylizer::<MarkdownFormatter>::default().fmt_bibliography(cited)
+            }
+            (ReferenceStyle::APA, Format::Plain) => {
+                ApaStylizer::<PlainTextFormatter>::default().fmt_bibliography(cited)
+            }
+            (ReferenceStyle::APA, Format::Html) => {
+                ApaStylizer::<HtmlFormatter>::default().fmt_bibliography(cited)
+            }
+            (ReferenceStyle::APA, Format::Markdown) => {
+                ApaStylizer::<MarkdownFormatter>::default().fmt_bibliography(cited)
+            }
+        }
+    }
+
+    /// The references of the cited entries without the labels of a reference list, with the
+    /// letters after the year that set them apart
+    pub fn fmt_references(&self, cited: &[Cited], format: Format) -> Vec<String> {
+        cited
+            .iter()
+            .map(|c| self.fmt_reference(&c.dated_entry(), format))
+            .collect()
+    }
+
+    pub fn fmt_reference(&self, entry: &BibEntry, format: Format) -> String {
         match (self, format) {
             (ReferenceStyle::IEEE, Format::Plain) => {
                 IeeeStylizer::<PlainTextFormatter>::default().fmt_reference(entry)
@@ -68,63 +507,162 @@ pub trait Stylizer {
     //required
     fn fmt_unpublished(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String;
     fn fmt_techreport(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String;
-    fn fmt_proceedings(&self, fields: BTreeMap<String, String>) -> String;
+    fn fmt_proceedings(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String;
     fn fmt_thesis(
         &self,
         kind: ThesisKind,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_misc(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_manual(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String;
-    fn fmt_misc(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String;
-    fn fmt_manual(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String;
     fn fmt_inproceedings(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String;
     fn fmt_incollection(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_inbook(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String;
-    fn fmt_inbook(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String;
     fn fmt_conference(
         &self,
-        authors: Vec<OwnedFullName>,
-        fields: BTreeMap<String, String>,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_booklet(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
     ) -> String;
-    fn fmt_booklet(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String;
-    fn fmt_book(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String;
-    fn fmt_article(&self, authors: Vec<OwnedFullName>, fields: BTreeMap<String, String>) -> String;
-    fn fmt_year_month(&self, year: Option<&String>, month: Option<&String>) -> String;
-    fn fmt_authors(&self, authors: Vec<OwnedFullName>) -> String;
+    fn fmt_book(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_article(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_online(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_software(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_dataset(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_patent(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_collection(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String;
+    fn fmt_periodical(&self, names: &NameLists, fields: &BTreeMap<String, String>) -> String;
+    fn fmt_standard(
+        &self,
+        authors: &[OwnedFullName],
+        names: &NameLists,
+        fields: &BTreeMap<String, String>,
+    ) -> String;
+    fn fmt_date(&self, date: Option<&EntryDate>) -> String;
+    fn fmt_authors(&self, authors: &[OwnedFullName]) -> String;
+    fn fmt_citation(
+        &self,
+        cited: Vec<Cited>,
+        mode: CitationMode,
+        prenote: Option<&str>,
+        postnote: Option<&str>,
+    ) -> String;
+    /// the reference list of the cited entries, in the order they are given
+    fn fmt_bibliography(&self, cited: Vec<Cited>) -> Vec<String>;
     // provided
-    fn fmt_reference(&self, entry: BibEntry) -> String {
-        let (kind, _key, authors, fields) = entry.into_components();
+    fn fmt_reference(&self, entry: &BibEntry) -> String {
+        let BibEntry {
+            kind,
+            authors,
+            names,
+            fields,
+            ..
+        } = entry;
 
         match kind {
-            EntryType::Article => Self::fmt_article(self, authors, fields),
-            EntryType::Book => Self::fmt_book(self, authors, fields),
-            EntryType::Booklet => Self::fmt_booklet(self, authors, fields),
-            EntryType::Conference => Self::fmt_conference(self, authors, fields),
-            EntryType::Inbook => Self::fmt_inbook(self, authors, fields),
-            EntryType::Incollection => Self::fmt_incollection(self, authors, fields),
-            EntryType::Inproceedings => Self::fmt_inproceedings(self, authors, fields),
-            EntryType::Manual => Self::fmt_manual(self, authors, fields),
-            EntryType::Mastersthesis => Self::fmt_thesis(self, ThesisKind::Msc, authors, fields),
-            EntryType::Misc => Self::fmt_misc(self, authors, fields),
-            EntryType::Phdthesis => Self::fmt_thesis(self, ThesisKind::Phd, authors, fields),
-            EntryType::Proceedings => Self::fmt_proceedings(self, fields),
-            EntryType::Techreport => Self::fmt_techreport(self, authors, fields),
-            EntryType::Unpublished => Self::fmt_unpublished(self, authors, fields),
+            EntryType::Article => Self::fmt_article(self, authors, names, fields),
+            EntryType::Book => Self::fmt_book(self, authors, names, fields),
+            EntryType::Booklet => Self::fmt_booklet(self, authors, names, fields),
+            EntryType::Conference => Self::fmt_conference(self, authors, names, fields),
+            EntryType::Inbook => Self::fmt_inbook(self, authors, names, fields),
+            EntryType::Incollection => Self::fmt_incollection(self, authors, names, fields),
+            EntryType::Inproceedings => Self::fmt_inproceedings(self, authors, names, fields),
+            EntryType::Manual => Self::fmt_manual(self, authors, names, fields),
+            EntryType::Mastersthesis => {
+                Self::fmt_thesis(self, ThesisKind::Msc, authors, names, fields)
+            }
+            EntryType::Misc => Self::fmt_misc(self, authors, names, fields),
+            EntryType::Phdthesis => Self::fmt_thesis(self, ThesisKind::Phd, authors, names, fields),
+            EntryType::Proceedings => Self::fmt_proceedings(self, names, fields),
+            EntryType::Techreport => Self::fmt_techreport(self, authors, names, fields),
+            EntryType::Unpublished => Self::fmt_unpublished(self, authors, names, fields),
+            EntryType::Online => Self::fmt_online(self, authors, names, fields),
+            EntryType::Software => Self::fmt_software(self, authors, names, fields),
+            EntryType::Dataset => Self::fmt_dataset(self, authors, names, fields),
+            EntryType::Report => Self::fmt_techreport(self, authors, names, fields),
+            EntryType::Thesis => {
+                let kind = ThesisKind::from_type(fields.get("type"));
+                Self::fmt_thesis(self, kind, authors, names, fields)
+            }
+            EntryType::Patent => Self::fmt_patent(self, authors, names, fields),
+            EntryType::Collection => Self::fmt_collection(self, names, fields),
+            EntryType::Mvbook => Self::fmt_book(self, authors, names, fields),
+            EntryType::Periodical => Self::fmt_periodical(self, names, fields),
+            EntryType::Standard => Self::fmt_standard(self, authors, names, fields),
+            EntryType::Other(_) => Self::fmt_misc(self, authors, names, fields),
         }
     }
 }
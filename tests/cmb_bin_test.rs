@@ -2,7 +2,7 @@ use anyhow::Result;
 use std::env;
 use std::fs::{read_to_string, File};
 use std::io::Write;
-use std::process::{Command, ExitStatus};
+use std::process::Command;
 use std::str;
 
 fn run_cmb() -> Command {
@@ -33,7 +33,7 @@ fn no_known_keys_errors() -> Result<()> {
 fn inplace_file() -> Result<()> {
     let initial_contets =
         "there once was a citation: \\cite{book}. adsflkjwoiejflkdslslsldlkfki nrgiwf";
-    let expected_contets = "there once was a citation: L. Susskind and G. Hrabovsky, Classical mechanics: the theoretical minimum. New York, NY: Penguin Random House, 2014.. adsflkjwoiejflkdslslsldlkfki nrgiwf";
+    let expected_contets = "there once was a citation: L. Susskind and G. Hrabovsky, Classical Mechanics: The Theoretical Minimum. New York, NY: Penguin Random House, 2014.. adsflkjwoiejflkdslslsldlkfki nrgiwf";
     let path = {
         let tmp_dir = env::temp_dir();
         let path = tmp_dir.join("test_file.txt");
@@ -51,26 +51,81 @@ fn inplace_file() -> Result<()> {
     Ok(())
 }
 #[test]
+fn inplace_file_pandoc() -> Result<()> {
+    let initial_contets = "as @book show [see @breiman2001, p. 5], email me@book.org";
+    let expected_contets = "as Susskind and Hrabovsky [1] show see [2, p. 5], email me@book.org";
+    let path = {
+        let tmp_dir = env::temp_dir();
+        let path = tmp_dir.join("test_file_pandoc.md");
+        let mut write_file = File::create(&path)?;
+        write_file.write_all(initial_contets.as_bytes())?;
+        path
+    };
+    run_cmb()
+        .args([
+            "-b",
+            "cite.bib",
+            "--syntax",
+            "pandoc",
+            "-i",
+            path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("could not run binary");
+    let contents = read_to_string(path)?;
+
+    assert_eq!(expected_contets, contents);
+    Ok(())
+}
+#[test]
+fn inplace_file_markers() -> Result<()> {
+    let initial_contets = "random forests \\parencite{breiman2001}.\n\n\\printbibliography\n";
+    let expected_contets = "random forests (Breiman, 2001).\n\nBreiman, L. (2001). Random forests. Machine learning, 45 (1), 5-32. https://doi.org/10.1023/a:1010933404324\n";
+    let path = {
+        let tmp_dir = env::temp_dir();
+        let path = tmp_dir.join("test_file_markers.tex");
+        let mut write_file = File::create(&path)?;
+        write_file.write_all(initial_contets.as_bytes())?;
+        path
+    };
+    run_cmb()
+        .args([
+            "-b",
+            "cite.bib",
+            "-s",
+            "apa",
+            "--markers",
+            "-i",
+            path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("could not run binary");
+    let contents = read_to_string(path)?;
+
+    assert_eq!(expected_contets, contents);
+    Ok(())
+}
+#[test]
 fn run_full_file_ieee() {
     let output = run_cmb()
         .args(["-b", "cite.bib", "--style", "ieee"])
         .output()
         .expect("could not run binary");
-    let expected = "L. Breiman, \"Random forests,\" Machine learning, vol. 45, no. 1, pp. 5-32, 2001. doi: https://doi.org/10.1023/a:1010933404324.
-J. Liao, X. Cao, L. Zhao, et al., \"The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists,\" FEMS Microbiology Ecology, vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: https://doi.org/10.1093/femsec/fiw174.
-P. J. Cohen, \"The independence of the continuum hypothesis,\" Proceedings of the National Academy of Sciences, vol. 50, no. 6, pp. 1143-1148, 1963.
-L. Susskind and G. Hrabovsky, Classical mechanics: the theoretical minimum. New York, NY: Penguin Random House, 2014.
-M. Swetla, Canoe tours in Sweden, Distributed at the Stockholm Tourist Office, Jul. 2015.
+    let expected = "L. Breiman, \"Random Forests,\" Machine learning, vol. 45, no. 1, pp. 5-32, 2001. doi: https://doi.org/10.1023/a:1010933404324.
+J. Liao, X. Cao, L. Zhao, et al., \"The Importance of Neutral and Niche Processes for Bacterial Community Assembly Differs Between Habitat Generalists and Specialists,\" FEMS Microbiology Ecology, vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: https://doi.org/10.1093/femsec/fiw174.
+P. J. Cohen, \"The Independence of the Continuum Hypothesis,\" Proceedings of the National Academy of Sciences, vol. 50, no. 6, pp. 1143-1148, 1963.
+L. Susskind and G. Hrabovsky, Classical Mechanics: The Theoretical Minimum. New York, NY: Penguin Random House, 2014.
+M. Swetla, Canoe Tours in Sweden, Distributed at the Stockholm Tourist Office, Jul. 2015.
 L. A. Urry, M. L. Cain, S. A. Wasserman, P. V. Minorsky, and J. B. Reece, \"Photosynthesis,\" in Campbell biology. New York, NY: Pearson, 2016, pp. 187-221.
-H. M. Shapiro, \"Flow cytometry: The glass is half full,\" in Flow cytometry protocols, T. S. Hawley and R. G. Hawley, Eds., New York, NY: Springer, 2018, pp. 1-10.
-P. Holleis, M. Wagner, and J. Koolwaaij, \"Studying mobile context-aware social services in the wild,\" in Proc. of the 6th Nordic Conf. on Human-Computer Interaction, ser. NordiCHI, New York, NY: ACM, 2010, pp. 207-216.
-R Core Team, R: A language and environment for statistical computing, R Foundation for Statistical Computing, Vienna, Austria, 2018.
-J. Tang, \"Spin structure of the nucleon in the asymptotic limit,\" M.S. thesis, Massachusetts Institute of Technology, Cambridge, MA, Sep. 1996.
-NASA, Pluto: The 'other' red planet, https://www.nasa.gov/nh/pluto-the-other-red-planet, Accessed: 2018-12-06, 2015.
-R. C. Rempel, \"Relaxation effects for coupled nuclear spins,\" Ph.D. dissertation, Stanford University, Stanford, CA, Jun. 1956.
-S. Stepney and S. Verlan, Eds., Proceedings of the 17th international conference on computation and natural computation, fontainebleau, france, vol. 10867, Lecture Notes in Computer Science, Cham, Switzerland: Springer, 2018
-V. Bennett, K. Bowman, and S. Wright, \"Wasatch Solar Project final report,\" Salt Lake City Corporation, Salt Lake City, UT, Tech. Rep. DOE-SLC-6903-1, Sep. 2018.
-M. Suresh, \"Evolution: A revised theory,\" unpublished.
+H. M. Shapiro, \"Flow Cytometry: The Glass Is Half Full,\" in Flow cytometry protocols, T. S. Hawley and R. G. Hawley, Eds., New York, NY: Springer, 2018, pp. 1-10.
+P. Holleis, M. Wagner, and J. Koolwaaij, \"Studying Mobile Context-Aware Social Services in the Wild,\" in Proc. of the 6th Nordic Conf. on Human-Computer Interaction, ser. NordiCHI, New York, NY: ACM, 2010, pp. 207-216.
+R Core Team, R: A Language and Environment for Statistical Computing, R Foundation for Statistical Computing, Vienna, Austria, 2018.
+J. Tang, \"Spin Structure of the Nucleon in the Asymptotic Limit,\" M.S. thesis, Massachusetts Institute of Technology, Cambridge, MA, Sep. 1996.
+NASA, Pluto: The 'Other' Red Planet, https://www.nasa.gov/nh/pluto-the-other-red-planet, Accessed: 2018-12-06, 2015.
+R. C. Rempel, \"Relaxation Effects for Coupled Nuclear Spins,\" Ph.D. dissertation, Stanford University, Stanford, CA, Jun. 1956.
+S. Stepney and S. Verlan, Eds., Proceedings of the 17th International Conference on Computation and Natural Computation, Fontainebleau, France, vol. 10867, Lecture Notes in Computer Science, Cham, Switzerland: Springer, 2018
+V. Bennett, K. Bowman, and S. Wright, \"Wasatch Solar Project Final Report,\" Salt Lake City Corporation, Salt Lake City, UT, Tech. Rep. DOE-SLC-6903-1, Sep. 2018.
+M. Suresh, \"Evolution: A Revised Theory,\" unpublished.
 J. Smith and J. Doe, \"The Effects of Climate Change,\" in Proceedings of the Annual Conference on Climate Change, B. Johnson, Ed., Climate Change Association, Los Angeles, CA: Springer, Jun. 2022, pp. 55-62.\n";
 
     assert_eq!(str::from_utf8(&output.stdout), Ok(expected));
@@ -82,7 +137,7 @@ fn run_unknown_key_and_book_ieee() {
         .args(["-b", "cite.bib", "--style", "ieee", "asdf", "book"])
         .output()
         .expect("could not run binary");
-    let expected_output = "L. Susskind and G. Hrabovsky, Classical mechanics: the theoretical minimum. New York, NY: Penguin Random House, 2014.\n";
+    let expected_output = "L. Susskind and G. Hrabovsky, Classical Mechanics: The Theoretical Minimum. New York, NY: Penguin Random House, 2014.\n";
     let expected_warning = "No entry for key asdf was found, skipping...\n";
 
     assert!(&output.status.success());
@@ -104,7 +159,7 @@ fn run_tb_ieee_html() {
         ])
         .output()
         .expect("could not run binary");
-    let expected_output = "J. Liao, X. Cao, L. Zhao, et al., \"The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists,\" <i>FEMS Microbiology Ecology,</i> vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: <a href=\"https://doi.org/10.1093/femsec/fiw174\">https://doi.org/10.1093/femsec/fiw174</a>.\n";
+    let expected_output = "J. Liao, X. Cao, L. Zhao, et al., \"The Importance of Neutral and Niche Processes for Bacterial Community Assembly Differs Between Habitat Generalists and Specialists,\" <i>FEMS Microbiology Ecology,</i> vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: <a href=\"https://doi.org/10.1093/femsec/fiw174\">https://doi.org/10.1093/femsec/fiw174</a>.\n";
 
     assert!(&output.status.success());
     assert_eq!(str::from_utf8(&output.stdout), Ok(expected_output));
@@ -124,7 +179,7 @@ fn run_tb_ieee_md() {
         ])
         .output()
         .expect("could not run binary");
-    let expected_output = "J. Liao, X. Cao, L. Zhao, et al., \"The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists,\" *FEMS Microbiology Ecology,* vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: [https://doi.org/10.1093/femsec/fiw174](https://doi.org/10.1093/femsec/fiw174).\n";
+    let expected_output = "J. Liao, X. Cao, L. Zhao, et al., \"The Importance of Neutral and Niche Processes for Bacterial Community Assembly Differs Between Habitat Generalists and Specialists,\" *FEMS Microbiology Ecology,* vol. 92, no. 11, Aug. 2016, issn: 0168-6496. doi: https://doi.org/10.1093/femsec/fiw174. [Online]. Available: [https://doi.org/10.1093/femsec/fiw174](https://doi.org/10.1093/femsec/fiw174).\n";
 
     assert!(&output.status.success());
     assert_eq!(str::from_utf8(&output.stdout), Ok(expected_output));
@@ -144,7 +199,7 @@ fn run_rf_apa_html() {
         ])
         .output()
         .expect("could not run binary");
-    let expected_output = "Liao, J., Cao, X., Zhao, L., Wang, J., Gao, Z., Wang, M. C., & Huang, Y. (2016). The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists. <i>FEMS Microbiology Ecology, 92</i> (11), <a href=\"https://doi.org/10.1093/femsec/fiw174\">https://doi.org/10.1093/femsec/fiw174</a>\n";
+    let expected_output = "Liao, J., Cao, X., Zhao, L., Wang, J., Gao, Z., Wang, M. C., & Huang, Y. (2016). The importance of neutral and niche processes for bacterial community assembly differs between habitat generalists and specialists. <i>FEMS Microbiology Ecology, 92</i> (11). <a href=\"https://doi.org/10.1093/femsec/fiw174\">https://doi.org/10.1093/femsec/fiw174</a>\n";
 
     assert!(&output.status.success());
     assert_eq!(str::from_utf8(&output.stdout), Ok(expected_output));
@@ -155,7 +210,7 @@ fn run_book_apa() {
         .args(["-b", "cite.bib", "--style", "apa", "book"])
         .output()
         .expect("could not run binary");
-    let expected_output = "Susskind, L., & Hrabovsky, G. (2014). Classical mechanics: the theoretical minimum. Penguin Random House.\n";
+    let expected_output = "Susskind, L., & Hrabovsky, G. (2014). Classical mechanics: The theoretical minimum. Penguin Random House.\n";
     let expected_warning = "";
 
     assert!(&output.status.success());
@@ -183,3 +238,99 @@ fn exists_on_fail_fast() {
         .expect("error running binary");
     assert!(&output.status.success(), "{:?}", &output);
 }
+#[test]
+fn parse_error_location() -> Result<()> {
+    let bib_contents = "@misc{foo,\n  title = {Foo},\n}\n@misc{bar,\n  title {Bar},\n}\n";
+    let path = {
+        let tmp_dir = env::temp_dir();
+        let path = tmp_dir.join("broken.bib");
+        let mut write_file = File::create(&path)?;
+        write_file.write_all(bib_contents.as_bytes())?;
+        path
+    };
+    let output = run_cmb()
+        .env("RUST_BACKTRACE", "0")
+        .args(["-b", path.to_str().unwrap()])
+        .output()
+        .expect("could not run binary");
+    let expected_error = format!(
+        "Error: {}:5:9: expected `=` after field name `title` in entry `bar`
+  |
+5 |   title {{Bar}},
+  |         ^\n",
+        path.display()
+    );
+
+    assert!(!&output.status.success(), "{:?}", output);
+    assert_eq!(str::from_utf8(&output.stdout), Ok(""));
+    assert_eq!(str::from_utf8(&output.stderr), Ok(expected_error.as_str()));
+    Ok(())
+}
+#[test]
+fn lenient_skips_malformed_entries() -> Result<()> {
+    let bib_contents = "@misc{foo,\n  author = {Jane Doe},\n  title = {Foo},\n  year = 2020,\n}\n@misc{bar,\n  title {Bar},\n}\n";
+    let path = {
+        let tmp_dir = env::temp_dir();
+        let path = tmp_dir.join("lenient.bib");
+        let mut write_file = File::create(&path)?;
+        write_file.write_all(bib_contents.as_bytes())?;
+        path
+    };
+    let output = run_cmb()
+        .env("RUST_BACKTRACE", "0")
+        .args(["-b", path.to_str().unwrap(), "--lenient", "-f", "plain"])
+        .output()
+        .expect("could not run binary");
+    let stderr = str::from_utf8(&output.stderr)?;
+
+    assert!(&output.status.success(), "{:?}", output);
+    assert_eq!(str::from_utf8(&output.stdout), Ok("J. Doe, Foo, 2020.\n"));
+    assert!(
+        stderr.contains(&format!(
+            "skipping malformed entry: {}:7:9: expected `=` after field name `title` in entry `bar`",
+            path.display()
+        )),
+        "{}",
+        stderr
+    );
+    Ok(())
+}
+#[test]
+fn dedup_writes_one_entry_per_cluster() -> Result<()> {
+    let tmp_dir = env::temp_dir();
+    let first = tmp_dir.join("dedup_first.bib");
+    File::create(&first)?.write_all(
+        b"@article{breiman2001,\n  author = {Leo Breiman},\n  title = {Random Forests},\n  journal = {Machine Learning},\n  year = 2001,\n}\n",
+    )?;
+    let second = tmp_dir.join("dedup_second.bib");
+    File::create(&second)?.write_all(
+        b"@article{rf,\n  author = {Breiman, L.},\n  title = {Random forests},\n  year = 2001,\n}\n\n@book{other,\n  title = {Other},\n}\n",
+    )?;
+    let merged = tmp_dir.join("dedup_merged.bib");
+    let output = run_cmb()
+        .env("RUST_BACKTRACE", "0")
+        .args([
+            "-b",
+            first.to_str().unwrap(),
+            "-b",
+            second.to_str().unwrap(),
+        ])
+        .args(["dedup", "-o", merged.to_str().unwrap()])
+        .output()
+        .expect("could not run binary");
+
+    assert!(&output.status.success(), "{:?}", output);
+    assert_eq!(
+        str::from_utf8(&output.stdout)?,
+        format!(
+            "breiman2001 ({}:1), rf ({}:1)\n",
+            first.display(),
+            second.display()
+        )
+    );
+    assert_eq!(
+        read_to_string(&merged)?,
+        "@article{breiman2001,\n  author = {Leo Breiman},\n  title = {Random Forests},\n  journal = {Machine Learning},\n  year = 2001,\n}\n\n@book{other,\n  title = {Other},\n}\n"
+    );
+    Ok(())
+}
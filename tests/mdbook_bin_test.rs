@@ -90,7 +90,9 @@ fn errors_on_nonexistant_config() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     assert!(!ExitStatus::success(&exit_code));
@@ -140,7 +142,9 @@ fn errors_on_no_config() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     assert!(!ExitStatus::success(&exit_code));
@@ -189,7 +193,9 @@ fn errors_on_fail_fast() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     assert!(!ExitStatus::success(&exit_code));
@@ -242,7 +248,9 @@ fn warns_without_fail_fast() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     let mut output = String::new();
@@ -305,7 +313,9 @@ fn run_without_citations_is_noop() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     let mut output = String::new();
@@ -352,7 +362,7 @@ fn respects_style() -> Result<()> {
                     "__non_exhaustive": null
                 }
             ]"##;
-    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"Susskind, L., & Hrabovsky, G. (2014). *Classical mechanics: the theoretical minimum*. Penguin Random House.","content":"Cohen, P. J. (1963). The independence of the continuum hypothesis. *Proceedings of the National Academy of Sciences, 50* (6), 1143-1148.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
+    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"Susskind, L., & Hrabovsky, G. (2014). *Classical mechanics: The theoretical minimum*. Penguin Random House.","content":"Cohen, P. J. (1963). The independence of the continuum hypothesis. *Proceedings of the National Academy of Sciences, 50* (6), 1143-1148.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
 
     let mut child = run_bin()
         .stdin(Stdio::piped())
@@ -361,7 +371,9 @@ fn respects_style() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     let mut output = String::new();
@@ -409,7 +421,7 @@ fn respects_format() -> Result<()> {
                     "__non_exhaustive": null
                 }
             ]"##;
-    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"L. Susskind and G. Hrabovsky, *Classical mechanics: the theoretical minimum*. New York, NY: Penguin Random House, 2014.","content":"P. J. Cohen, \"The independence of the continuum hypothesis,\" *Proceedings of the National Academy of Sciences,* vol. 50, no. 6, pp. 1143-1148, 1963.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
+    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"L. Susskind and G. Hrabovsky, *Classical Mechanics: The Theoretical Minimum*. New York, NY: Penguin Random House, 2014.","content":"P. J. Cohen, \"The Independence of the Continuum Hypothesis,\" *Proceedings of the National Academy of Sciences,* vol. 50, no. 6, pp. 1143-1148, 1963.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
 
     let mut child = run_bin()
         .stdin(Stdio::piped())
@@ -418,7 +430,9 @@ fn respects_format() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     let mut output = String::new();
@@ -466,7 +480,7 @@ fn citation_in_content_and_title() -> Result<()> {
                     "__non_exhaustive": null
                 }
             ]"##;
-    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"L. Susskind and G. Hrabovsky, *Classical mechanics: the theoretical minimum*. New York, NY: Penguin Random House, 2014.","content":"P. J. Cohen, \"The independence of the continuum hypothesis,\" *Proceedings of the National Academy of Sciences,* vol. 50, no. 6, pp. 1143-1148, 1963.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
+    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"L. Susskind and G. Hrabovsky, *Classical Mechanics: The Theoretical Minimum*. New York, NY: Penguin Random House, 2014.","content":"P. J. Cohen, \"The Independence of the Continuum Hypothesis,\" *Proceedings of the National Academy of Sciences,* vol. 50, no. 6, pp. 1143-1148, 1963.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
 
     let mut child = run_bin()
         .stdin(Stdio::piped())
@@ -475,7 +489,9 @@ fn citation_in_content_and_title() -> Result<()> {
         .spawn()
         .expect("failed to run bsinary");
     let mut stdin = child.stdin.take().unwrap();
-    stdin.write_all(input_json.as_bytes())?;
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
     drop(stdin);
     let exit_code = child.wait().expect("DOH!+");
     let mut output = String::new();
@@ -485,3 +501,134 @@ fn citation_in_content_and_title() -> Result<()> {
     assert_eq!(output, expected_output_json);
     Ok(())
 }
+
+#[test]
+fn reports_parse_error_location() -> Result<()> {
+    let bib_path = std::env::temp_dir().join("mdbook_broken.bib");
+    std::fs::write(&bib_path, "@misc{foo,\n  title = {Foo}\n  author = ,\n}\n")?;
+    let input_json = r##"[
+                {
+                    "root": "/path/to/book",
+                    "config": {
+                        "book": {
+                            "authors": ["AUTHOR"],
+                            "language": "en",
+                            "multilingual": false,
+                            "src": "src",
+                            "title": "TITLE"
+                        },
+                        "preprocessor": {
+                            "citations": {"bibfile":"BIB_PATH"}
+                        }
+                    },
+                    "renderer": "html",
+                    "mdbook_version": "0.4.40"
+                },
+                {
+                    "sections": [],
+                    "__non_exhaustive": null
+                }
+            ]"##
+    .replace("BIB_PATH", bib_path.to_str().unwrap());
+
+    let mut child = run_bin()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run bsinary");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
+    drop(stdin);
+    let exit_code = child.wait().expect("DOH!+");
+    let mut warnings = String::new();
+    let mut stderr = child.stderr.unwrap();
+    stderr.read_to_string(&mut warnings)?;
+    assert!(!ExitStatus::success(&exit_code));
+    assert!(
+        warnings.starts_with(&format!(
+            "{}:3:12: expected a value for field `author`",
+            bib_path.display()
+        )),
+        "{}",
+        warnings
+    );
+    assert!(warnings.contains("3 |   author = ,\n"), "{}", warnings);
+    Ok(())
+}
+
+#[test]
+fn lenient_skips_malformed_entries() -> Result<()> {
+    let bib_path = std::env::temp_dir().join("mdbook_lenient.bib");
+    std::fs::write(
+        &bib_path,
+        "@misc{foo,\n  author = {Jane Doe},\n  title = {Foo},\n  year = 2020,\n}\n@misc{bar,\n  author = ,\n}\n",
+    )?;
+    let input_json = r##"[
+                {
+                    "root": "/path/to/book",
+                    "config": {
+                        "book": {
+                            "authors": ["AUTHOR"],
+                            "language": "en",
+                            "multilingual": false,
+                            "src": "src",
+                            "title": "TITLE"
+                        },
+                        "preprocessor": {
+                            "citations": {"bibfile":"BIB_PATH", "lenient": true}
+                        }
+                    },
+                    "renderer": "html",
+                    "mdbook_version": "0.4.40"
+                },
+                {
+                    "sections": [
+                        {
+                            "Chapter": {
+                                "name": "Chapter 1",
+                                "content": "\\cite{foo}",
+                                "number": [1],
+                                "sub_items": [],
+                                "path": "chapter_1.md",
+                                "source_path": "chapter_1.md",
+                                "parent_names": []
+                            }
+                        }
+                    ],
+                    "__non_exhaustive": null
+                }
+            ]"##
+    .replace("BIB_PATH", bib_path.to_str().unwrap());
+    let expected_output_json = r##"{"sections":[{"Chapter":{"name":"Chapter 1","content":"J. Doe, Foo, 2020.","number":[1],"sub_items":[],"path":"chapter_1.md","source_path":"chapter_1.md","parent_names":[]}}],"__non_exhaustive":null}"##;
+
+    let mut child = run_bin()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run bsinary");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(input_json.as_bytes())
+        .expect("failed to write input");
+    drop(stdin);
+    let exit_code = child.wait().expect("DOH!+");
+    let mut output = String::new();
+    let mut warnings = String::new();
+    child.stdout.unwrap().read_to_string(&mut output)?;
+    child.stderr.unwrap().read_to_string(&mut warnings)?;
+    assert!(ExitStatus::success(&exit_code), "{}", warnings);
+    assert_eq!(output, expected_output_json);
+    assert!(
+        warnings.starts_with(&format!(
+            "[WARN ] skipping malformed entry: {}:7:12: expected a value for field `author`",
+            bib_path.display()
+        )),
+        "{}",
+        warnings
+    );
+    Ok(())
+}